@@ -0,0 +1,258 @@
+/// Everything that can be triggered from the board, by a key or from the
+/// command palette
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Action {
+    Up,
+    Down,
+    Left,
+    Right,
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    MoveTo,
+    /// Selects the column at an index, counting from zero
+    SelectColumn(usize),
+    PageUp,
+    PageDown,
+    First,
+    Last,
+    CreateRow,
+    EditRow,
+    DeleteRow,
+    ToggleMark,
+    MarkRange,
+    ClearMarks,
+    MoveMarked,
+    ArchiveRows,
+    ShowArchive,
+    EditLabels,
+    SetPriority,
+    Copy,
+    Cut,
+    Paste,
+    Undo,
+    CreateColumn,
+    InsertColumnBefore,
+    InsertColumnAfter,
+    EditColumn,
+    DeleteColumn,
+    MoveColumnLeft,
+    MoveColumnRight,
+    DuplicateColumn,
+    DuplicateEmptyColumn,
+    WidenColumn,
+    NarrowColumn,
+    ResetColumnWidth,
+    ToggleCollapsed,
+    CycleDensity,
+    FindCard,
+    SwitchBoard,
+    NextTab,
+    PreviousTab,
+    CloseTab,
+    ExportMarkdown,
+    CommandPalette,
+    Help,
+    Quit,
+}
+
+const COLUMN_NAMES: [&str; 9] = [
+    "column_1", "column_2", "column_3", "column_4", "column_5", "column_6", "column_7", "column_8",
+    "column_9",
+];
+const COLUMN_TITLES: [&str; 9] = [
+    "Select Column 1",
+    "Select Column 2",
+    "Select Column 3",
+    "Select Column 4",
+    "Select Column 5",
+    "Select Column 6",
+    "Select Column 7",
+    "Select Column 8",
+    "Select Column 9",
+];
+
+impl Action {
+    pub const ALL: [Action; 60] = [
+        Action::Up,
+        Action::Down,
+        Action::Left,
+        Action::Right,
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::MoveTo,
+        Action::SelectColumn(0),
+        Action::SelectColumn(1),
+        Action::SelectColumn(2),
+        Action::SelectColumn(3),
+        Action::SelectColumn(4),
+        Action::SelectColumn(5),
+        Action::SelectColumn(6),
+        Action::SelectColumn(7),
+        Action::SelectColumn(8),
+        Action::PageUp,
+        Action::PageDown,
+        Action::First,
+        Action::Last,
+        Action::CreateRow,
+        Action::EditRow,
+        Action::DeleteRow,
+        Action::ToggleMark,
+        Action::MarkRange,
+        Action::ClearMarks,
+        Action::MoveMarked,
+        Action::ArchiveRows,
+        Action::ShowArchive,
+        Action::EditLabels,
+        Action::SetPriority,
+        Action::Copy,
+        Action::Cut,
+        Action::Paste,
+        Action::Undo,
+        Action::CreateColumn,
+        Action::InsertColumnBefore,
+        Action::InsertColumnAfter,
+        Action::EditColumn,
+        Action::DeleteColumn,
+        Action::MoveColumnLeft,
+        Action::MoveColumnRight,
+        Action::DuplicateColumn,
+        Action::DuplicateEmptyColumn,
+        Action::WidenColumn,
+        Action::NarrowColumn,
+        Action::ResetColumnWidth,
+        Action::ToggleCollapsed,
+        Action::CycleDensity,
+        Action::FindCard,
+        Action::SwitchBoard,
+        Action::NextTab,
+        Action::PreviousTab,
+        Action::CloseTab,
+        Action::ExportMarkdown,
+        Action::CommandPalette,
+        Action::Help,
+        Action::Quit,
+    ];
+
+    /// The name used to refer to the action in the config file
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Up => "up",
+            Self::Down => "down",
+            Self::Left => "left",
+            Self::Right => "right",
+            Self::MoveUp => "move_up",
+            Self::MoveDown => "move_down",
+            Self::MoveLeft => "move_left",
+            Self::MoveRight => "move_right",
+            Self::MoveTo => "move_to",
+            Self::SelectColumn(i) => COLUMN_NAMES[*i],
+            Self::PageUp => "page_up",
+            Self::PageDown => "page_down",
+            Self::First => "first",
+            Self::Last => "last",
+            Self::CreateRow => "create_item",
+            Self::EditRow => "edit_item",
+            Self::DeleteRow => "delete_item",
+            Self::ToggleMark => "toggle_mark",
+            Self::MarkRange => "mark_range",
+            Self::ClearMarks => "clear_marks",
+            Self::MoveMarked => "move_marked",
+            Self::ArchiveRows => "archive_item",
+            Self::ShowArchive => "show_archive",
+            Self::EditLabels => "edit_labels",
+            Self::SetPriority => "set_priority",
+            Self::Copy => "copy",
+            Self::Cut => "cut",
+            Self::Paste => "paste",
+            Self::Undo => "undo",
+            Self::CreateColumn => "create_column",
+            Self::InsertColumnBefore => "insert_column_before",
+            Self::InsertColumnAfter => "insert_column_after",
+            Self::EditColumn => "edit_column",
+            Self::DeleteColumn => "delete_column",
+            Self::MoveColumnLeft => "move_column_left",
+            Self::MoveColumnRight => "move_column_right",
+            Self::DuplicateColumn => "duplicate_column",
+            Self::DuplicateEmptyColumn => "duplicate_empty_column",
+            Self::WidenColumn => "widen_column",
+            Self::NarrowColumn => "narrow_column",
+            Self::ResetColumnWidth => "reset_column_width",
+            Self::ToggleCollapsed => "toggle_collapsed",
+            Self::CycleDensity => "toggle_view",
+            Self::FindCard => "find_card",
+            Self::SwitchBoard => "switch_board",
+            Self::NextTab => "next_tab",
+            Self::PreviousTab => "previous_tab",
+            Self::CloseTab => "close_tab",
+            Self::ExportMarkdown => "export_markdown",
+            Self::CommandPalette => "command_palette",
+            Self::Help => "help",
+            Self::Quit => "quit",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Action> {
+        Self::ALL.into_iter().find(|action| action.name() == name)
+    }
+
+    pub fn title(&self) -> &str {
+        match self {
+            Self::Up => "Select Item Above",
+            Self::Down => "Select Item Below",
+            Self::Left => "Select Column Left",
+            Self::Right => "Select Column Right",
+            Self::MoveUp => "Move Item Up",
+            Self::MoveDown => "Move Item Down",
+            Self::MoveLeft => "Move Item Left",
+            Self::MoveRight => "Move Item Right",
+            Self::MoveTo => "Move Item To Column...",
+            Self::SelectColumn(i) => COLUMN_TITLES[*i],
+            Self::PageUp => "Page Up",
+            Self::PageDown => "Page Down",
+            Self::First => "Select First Item",
+            Self::Last => "Select Last Item",
+            Self::CreateRow => "Create Item",
+            Self::EditRow => "Edit Item",
+            Self::DeleteRow => "Delete Item",
+            Self::ToggleMark => "Mark/Unmark Item",
+            Self::MarkRange => "Mark Items Up To Here",
+            Self::ClearMarks => "Unmark All Items",
+            Self::MoveMarked => "Move Marked Items Here",
+            Self::ArchiveRows => "Archive Item",
+            Self::ShowArchive => "Show Archived Items",
+            Self::EditLabels => "Add/Remove Labels",
+            Self::SetPriority => "Set Priority",
+            Self::Copy => "Copy Item",
+            Self::Cut => "Cut Item",
+            Self::Paste => "Paste Items Below",
+            Self::Undo => "Undo",
+            Self::CreateColumn => "Create Column",
+            Self::InsertColumnBefore => "Insert Column Before",
+            Self::InsertColumnAfter => "Insert Column After",
+            Self::EditColumn => "Edit Column",
+            Self::DeleteColumn => "Delete Column",
+            Self::MoveColumnLeft => "Move Column Left",
+            Self::MoveColumnRight => "Move Column Right",
+            Self::DuplicateColumn => "Duplicate Column",
+            Self::DuplicateEmptyColumn => "Duplicate Column Without Items",
+            Self::WidenColumn => "Widen Column",
+            Self::NarrowColumn => "Narrow Column",
+            Self::ResetColumnWidth => "Reset Column Width",
+            Self::ToggleCollapsed => "Collapse/Expand Column",
+            Self::CycleDensity => "Toggle View (Full/Summary/Compact)",
+            Self::FindCard => "Find Card",
+            Self::SwitchBoard => "Open Board",
+            Self::NextTab => "Next Tab",
+            Self::PreviousTab => "Previous Tab",
+            Self::CloseTab => "Close Tab",
+            Self::ExportMarkdown => "Export Board to Markdown",
+            Self::CommandPalette => "Command Palette",
+            Self::Help => "Help",
+            Self::Quit => "Quit",
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use crossterm::event::KeyEvent;
+
+use super::{
+    board::Board,
+    fuzzy,
+    fuzzy_list::{FuzzyEntry, FuzzyList, SearchField},
+};
+
+/// An archived card, along with the column it was archived from
+pub struct ArchiveEntry {
+    pub column: String,
+    pub id: u64,
+    pub title: String,
+    pub description: String,
+}
+
+impl FuzzyEntry for ArchiveEntry {
+    fn score(&self, query: &str) -> Option<i64> {
+        [
+            fuzzy::score(query, &self.title).map(|score| score * 2),
+            fuzzy::score(query, &self.description),
+        ]
+        .into_iter()
+        .flatten()
+        .max()
+    }
+}
+
+/// Lists the archived cards of a board, to restore one of them
+pub struct ArchiveState<'a> {
+    pub list: FuzzyList<'a, ArchiveEntry>,
+}
+
+impl<'a> ArchiveState<'a> {
+    pub fn new(board: &Board) -> Self {
+        let entries = board
+            .columns
+            .iter()
+            .flat_map(|col| {
+                col.archived.iter().rev().map(|row| ArchiveEntry {
+                    column: col.title.clone(),
+                    id: row.id,
+                    title: row.title.clone(),
+                    description: row.description.clone(),
+                })
+            })
+            .collect();
+        let field = SearchField {
+            title: "Search",
+            placeholder: "Search archived cards...",
+        };
+        Self {
+            list: FuzzyList::new(field, entries),
+        }
+    }
+
+    /// The ID of the selected card
+    pub fn selected(&self) -> Option<u64> {
+        self.list.selected().map(|entry| entry.id)
+    }
+
+    pub fn on_keypress(&mut self, key: KeyEvent) {
+        self.list.on_keypress(key);
+    }
+}
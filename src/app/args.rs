@@ -1,9 +1,15 @@
-use clap::{Args, Parser, Subcommand};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use std::path::PathBuf;
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 pub struct Cli {
-    pub filename: Option<String>,
+    /// Names of the boards to open, each in its own tab
+    pub filenames: Vec<String>,
+
+    /// Path to the config file, defaults to ~/.config/kb/config.toml
+    #[arg(long, global = true)]
+    pub config: Option<PathBuf>,
 
     #[command(subcommand)]
     pub command: Option<Commands>,
@@ -13,9 +19,205 @@ pub struct Cli {
 pub enum Commands {
     /// Adds files to myapp
     New(NewBoardArgs),
+    /// Writes a board in another format
+    Export(ExportArgs),
+    /// Reads cards from another format into a new or existing board
+    Import(ImportArgs),
+    /// Brings a board and a todo.txt file up to date with each other
+    Sync(SyncArgs),
+    /// Lists the cards of a board by column
+    List(ListArgs),
+    /// Shows a card with all its details
+    Show(ShowArgs),
+    /// Finds cards whose title, description or labels contain some text
+    Search(SearchArgs),
+    /// Counts the cards of a board by column, label and priority
+    Stats(QueryArgs),
+    /// Stores a board in another file format, or as a directory
+    Convert(ConvertArgs),
 }
 
 #[derive(Args)]
 pub struct NewBoardArgs {
     pub filename: Option<String>,
 }
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ExportFormat {
+    Markdown,
+    /// One row per card, with its column and position
+    Csv,
+    /// A single page laid out like the board
+    Html,
+    /// A calendar with a to-do for each card with a due date
+    Ics,
+    /// One task per card, completed for cards in done columns
+    Todotxt,
+}
+
+#[derive(Args)]
+pub struct ExportArgs {
+    /// Name of the board to export
+    pub filename: Option<String>,
+
+    #[arg(long, value_enum, default_value = "markdown")]
+    pub format: ExportFormat,
+
+    /// Only export these columns, separated by commas
+    #[arg(long, value_delimiter = ',')]
+    pub columns: Vec<String>,
+
+    /// Leave out these columns, separated by commas
+    #[arg(long, value_delimiter = ',')]
+    pub exclude: Vec<String>,
+
+    /// Write cards as task list items, checked in a "Done" column
+    #[arg(long)]
+    pub tasks: bool,
+
+    /// Use a dark background in HTML exports
+    #[arg(long)]
+    pub dark: bool,
+
+    /// Write all-day events instead of to-dos in calendar exports
+    #[arg(long)]
+    pub events: bool,
+
+    /// Column whose cards are completed tasks in todo.txt exports, as well
+    /// as any column called Done
+    #[arg(long)]
+    pub done_column: Option<String>,
+
+    /// File to write to, instead of standard output
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ImportFormat {
+    Markdown,
+    /// A board exported from Trello as JSON
+    Trello,
+    /// Cards written by `kb export --format csv`
+    Csv,
+    /// Tasks in the todo.txt format
+    Todotxt,
+}
+
+#[derive(Args)]
+pub struct ImportArgs {
+    /// File to read the cards from
+    pub file: PathBuf,
+
+    #[arg(long, alias = "format", value_enum, default_value = "markdown")]
+    pub from: ImportFormat,
+
+    /// Add the cards to this existing board instead of creating a new one.
+    /// Cards with the ID of a card on the board replace it.
+    #[arg(long, conflicts_with = "name")]
+    pub into: Option<String>,
+
+    /// Name of the new board, defaults to the name of the imported file
+    #[arg(long)]
+    pub name: Option<String>,
+
+    /// Column completed todo.txt tasks go to, instead of the board's first
+    /// done column
+    #[arg(long)]
+    pub done_column: Option<String>,
+
+    /// Import without asking for confirmation
+    #[arg(short, long)]
+    pub yes: bool,
+}
+
+#[derive(Args)]
+pub struct SyncArgs {
+    /// The todo.txt file, created if it doesn't exist
+    pub file: PathBuf,
+
+    /// Name of the board, the default board if not given
+    #[arg(long)]
+    pub board: Option<String>,
+
+    /// Column completed tasks go to, instead of the board's first done
+    /// column
+    #[arg(long)]
+    pub done_column: Option<String>,
+}
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+pub enum OutputFormat {
+    Text,
+    /// JSON with a versioned schema, for scripts
+    Json,
+}
+
+/// Options shared by the commands that read a board
+#[derive(Args)]
+pub struct QueryArgs {
+    /// Name of the board, the default board if not given
+    #[arg(long)]
+    pub board: Option<String>,
+
+    #[arg(long, value_enum, default_value = "text")]
+    pub format: OutputFormat,
+}
+
+#[derive(Args)]
+pub struct ListArgs {
+    #[command(flatten)]
+    pub query: QueryArgs,
+
+    /// Only list these columns, separated by commas
+    #[arg(long, value_delimiter = ',')]
+    pub columns: Vec<String>,
+
+    /// Include archived cards
+    #[arg(long)]
+    pub archived: bool,
+}
+
+#[derive(Args)]
+pub struct ShowArgs {
+    /// ID of the card
+    pub id: u64,
+
+    #[command(flatten)]
+    pub query: QueryArgs,
+}
+
+#[derive(Args)]
+pub struct SearchArgs {
+    /// Text to look for, ignoring case
+    pub text: String,
+
+    #[command(flatten)]
+    pub query: QueryArgs,
+
+    /// Include archived cards
+    #[arg(long)]
+    pub archived: bool,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum StorageFormat {
+    Json,
+    Yaml,
+    Toml,
+    /// A directory with a manifest and a Markdown file for each card
+    Directory,
+}
+
+#[derive(Args)]
+pub struct ConvertArgs {
+    /// Name of the board to convert
+    pub filename: Option<String>,
+
+    #[arg(long, value_enum)]
+    pub to: StorageFormat,
+
+    /// Keep the board's old file or directory
+    #[arg(long)]
+    pub keep: bool,
+}
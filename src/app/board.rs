@@ -1,23 +1,63 @@
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use serde::{Deserialize, Serialize};
-use std::fs;
-use tui::widgets::TableState;
+use std::{
+    collections::{hash_map::RandomState, HashSet},
+    hash::{BuildHasher, Hasher},
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tui::{layout::Rect, widgets::TableState};
+
+use super::storage::{storage, FileFormat};
+use crate::formats::ImportColumn;
+
+/// Directory holding named boards
+pub const BOARD_DIR: &str = ".kb";
+
+/// Columns without a width of their own are never drawn narrower than this,
+/// the board scrolls sideways instead
+pub const MIN_COLUMN_WIDTH: u16 = 24;
+/// The narrowest a column can be resized to
+const NARROWEST_COLUMN: u16 = 10;
+/// Width of a collapsed column, including its borders
+const COLLAPSED_WIDTH: u16 = 3;
+/// How much a column grows or shrinks with each resize
+const RESIZE_STEP: u16 = 4;
+/// How many changes can be undone
+const UNDO_LIMIT: usize = 50;
 
 #[derive(Serialize, Deserialize)]
 pub struct Board {
     pub title: Option<String>,
     pub columns: Vec<Column>,
+    /// The last card ID handed out, card IDs are unique within a board
+    #[serde(default)]
+    pub(super) next_id: u64,
+    #[serde(default, skip_serializing_if = "Density::is_full")]
+    pub density: Density,
     #[serde(default, skip_serializing)]
     pub selected_column: usize,
+    /// Index of the first column in view
+    #[serde(skip)]
+    pub column_offset: usize,
     #[serde(default, skip_serializing)]
     filename: String,
+    /// IDs of the cards marked for a bulk operation. Marks stay on cards
+    /// that are moved or relabelled, so that they can be worked on further.
+    #[serde(skip)]
+    marked: HashSet<u64>,
+    /// The card last marked or unmarked, where range selections start
+    #[serde(skip)]
+    mark_anchor: Option<u64>,
+    /// The columns and selected column before each bulk or column change,
+    /// most recent last
+    #[serde(skip)]
+    undo: Vec<(Vec<Column>, usize)>,
 }
 
 impl Board {
     pub fn create(filename: &str) -> color_eyre::Result<Self> {
-        _ = std::fs::File::create(filename)?;
         let board = Self::new(None, filename);
-        _ = board.save()?;
+        board.save()?;
         Ok(board)
     }
 
@@ -29,18 +69,35 @@ impl Board {
                 Column::new("In Progress".to_owned()),
                 Column::new("Done".to_owned()),
             ],
+            next_id: 0,
+            density: Density::default(),
             selected_column: 0,
+            column_offset: 0,
             filename: filename.to_string(),
+            marked: HashSet::new(),
+            mark_anchor: None,
+            undo: Vec::new(),
         }
     }
 
+    /// The board's own title, or else its file name without the directory
+    /// and extension
     pub fn title(&self) -> String {
-        self.title.clone().unwrap_or(
-            self.filename
-                .replace(".json", "_")
-                .replace("_", " ")
-                .clone(),
-        )
+        self.title.clone().unwrap_or_else(|| {
+            let path = Path::new(&self.filename);
+            let name = match path.extension().and_then(|ext| ext.to_str()) {
+                Some(ext) if FileFormat::EXTENSIONS.contains(&ext) => path.file_stem(),
+                _ => path.file_name(),
+            };
+            let name = name.map_or(self.filename.clone(), |name| {
+                name.to_string_lossy().to_string()
+            });
+            name.replace('_', " ").trim().to_string()
+        })
+    }
+
+    pub fn filename(&self) -> &str {
+        &self.filename
     }
 
     pub fn selected_column(&mut self) -> Option<&mut Column> {
@@ -50,86 +107,519 @@ impl Board {
         Some(&mut self.columns[self.selected_column])
     }
     pub fn selected_row(&mut self) -> Option<&mut Row> {
-        let Some(col) = self.selected_column() else { return None;};
-        let Some(index) = col.state.selected() else { return None; };
+        let col = self.selected_column()?;
+        let index = col.state.selected()?;
         Some(&mut col.rows[index])
     }
 
-    pub fn on_keypress(&mut self, key: &KeyEvent) {
-        let should_move = key.modifiers == KeyModifiers::SHIFT;
-        match key.code {
-            KeyCode::Down | KeyCode::Char('j') => self.down(should_move),
-            KeyCode::Up | KeyCode::Char('k') => self.up(should_move),
-            KeyCode::Left | KeyCode::Char('h') => self.left(should_move),
-            KeyCode::Right | KeyCode::Char('l') => self.right(should_move),
-            _ => {}
+    /// Selects the card with the given ID, returning whether it was found
+    pub fn select_card(&mut self, id: u64) -> bool {
+        let position = self.columns.iter().enumerate().find_map(|(i, col)| {
+            col.rows
+                .iter()
+                .position(|row| row.id == id)
+                .map(|index| (i, index))
+        });
+        let Some((column, index)) = position else { return false };
+        self.select_column(column);
+        self.columns[column].state.select(Some(index));
+        true
+    }
+
+    /// Selects a card, or just the column if `row` is `None`
+    pub fn select_row(&mut self, column: usize, row: Option<usize>) {
+        if column >= self.columns.len() {
+            return;
         }
+        if column != self.selected_column {
+            self.select_column(column);
+        }
+        let col = &mut self.columns[column];
+        if let Some(row) = row {
+            if row < col.rows.len() {
+                col.state.select(Some(row));
+            }
+        }
+    }
+
+    /// Finds the column, and the card if any, drawn at a position on screen
+    pub fn card_at(&self, x: u16, y: u16) -> Option<(usize, Option<usize>)> {
+        let column = self
+            .columns
+            .iter()
+            .position(|col| contains(col.area, x, y))?;
+        let row = self.columns[column]
+            .row_areas
+            .iter()
+            .find(|(_, area)| contains(*area, x, y))
+            .map(|(index, _)| *index);
+        Some((column, row))
     }
 
     pub fn insert_row(&mut self, title: String, description: String) {
+        let id = self.next_id();
         let Some(col) = self.selected_column() else { return };
-        col.rows.push(Row { title, description });
+        col.rows.push(Row {
+            id,
+            key: card_key(),
+            title,
+            description,
+            labels: Vec::new(),
+            priority: None,
+            due: None,
+        });
         _ = self.save();
     }
 
     pub fn update_row(&mut self, title: String, description: String) {
         let Some(row) = self.selected_row() else { return };
-        *row = Row { title, description };
+        row.title = title;
+        row.description = description;
         _ = self.save()
     }
 
-    pub fn delete_row(&mut self) {
-        let Some(col) = self.selected_column() else { return };
-        let Some(index) = col.state.selected() else { return };
+    /// Deletes the marked cards, or the selected one if none are marked
+    pub fn delete_rows(&mut self) {
+        let targets = self.targets();
+        if targets.is_empty() {
+            return;
+        }
+        self.snapshot();
+        self.take_rows(&targets);
+        self.marked.clear();
+        _ = self.save();
+    }
 
-        let new_selection: Option<usize> = {
-            if col.rows.is_empty() {
-                None
-            } else {
-                if index == 0 {
-                    Some(0)
+    /// Moves the marked cards, or the selected one if none are marked, to
+    /// their column's archive
+    pub fn archive_rows(&mut self) {
+        let targets = self.targets();
+        if targets.is_empty() {
+            return;
+        }
+        self.snapshot();
+        for (column, row) in self.take_rows(&targets) {
+            self.columns[column].archived.push(row);
+        }
+        self.marked.clear();
+        _ = self.save();
+    }
+
+    /// Puts an archived card back at the bottom of the column it was
+    /// archived from, and selects it
+    pub fn unarchive(&mut self, id: u64) {
+        let position = self.columns.iter().enumerate().find_map(|(i, col)| {
+            let index = col.archived.iter().position(|row| row.id == id)?;
+            Some((i, index))
+        });
+        let Some((column, index)) = position else { return };
+        self.snapshot();
+        let row = self.columns[column].archived.remove(index);
+        self.columns[column].rows.push(row);
+        self.select_row(column, Some(self.columns[column].rows.len() - 1));
+        _ = self.save();
+    }
+
+    /// Moves the marked cards to the bottom of the selected column, keeping
+    /// their order on the board
+    pub fn move_marked(&mut self) {
+        if self.marked.is_empty() || self.selected_column >= self.columns.len() {
+            return;
+        }
+        self.snapshot();
+        let targets = self.targets();
+        let rows = self.take_rows(&targets);
+        let col = &mut self.columns[self.selected_column];
+        let first = col.rows.len();
+        col.rows.extend(rows.into_iter().map(|(_, row)| row));
+        col.state.select(Some(first));
+        _ = self.save();
+    }
+
+    /// Moves the marked cards, or the selected one if none are marked, to
+    /// the top or bottom of a column and selects the first of them
+    pub fn move_rows_to(&mut self, column: usize, top: bool) {
+        if column >= self.columns.len() {
+            return;
+        }
+        let targets = self.targets();
+        if targets.is_empty() {
+            return;
+        }
+        self.snapshot();
+        let rows = self.take_rows(&targets);
+        self.select_column(column);
+        let col = &mut self.columns[column];
+        let index = if top { 0 } else { col.rows.len() };
+        col.rows
+            .splice(index..index, rows.into_iter().map(|(_, row)| row));
+        col.state.select(Some(index));
+        _ = self.save();
+    }
+
+    /// Adds labels to, or removes labels prefixed with `-` from, the marked
+    /// cards or the selected one if none are marked
+    pub fn edit_labels(&mut self, labels: &[String]) {
+        let targets = self.targets();
+        if targets.is_empty() {
+            return;
+        }
+        self.snapshot();
+        for row in self.rows_mut().filter(|row| targets.contains(&row.id)) {
+            for label in labels {
+                if let Some(label) = label.strip_prefix('-') {
+                    row.labels.retain(|l| l != label);
+                } else if !row.labels.contains(label) {
+                    row.labels.push(label.clone());
+                }
+            }
+        }
+        _ = self.save();
+    }
+
+    /// Sets the priority of the marked cards, or the selected one if none
+    /// are marked
+    pub fn set_priority(&mut self, priority: Option<char>) {
+        let targets = self.targets();
+        if targets.is_empty() {
+            return;
+        }
+        self.snapshot();
+        for row in self.rows_mut().filter(|row| targets.contains(&row.id)) {
+            row.priority = priority;
+        }
+        _ = self.save();
+    }
+
+    /// Copies of the marked cards, or the selected one if none are marked,
+    /// in the order they appear on the board
+    pub fn copy_rows(&mut self) -> Vec<Row> {
+        let targets = self.targets();
+        self.columns
+            .iter()
+            .flat_map(|col| col.rows.iter())
+            .filter(|row| targets.contains(&row.id))
+            .cloned()
+            .collect()
+    }
+
+    /// Removes the marked cards, or the selected one if none are marked,
+    /// returning them in the order they appeared on the board
+    pub fn cut_rows(&mut self) -> Vec<Row> {
+        let targets = self.targets();
+        if targets.is_empty() {
+            return Vec::new();
+        }
+        self.snapshot();
+        let rows = self.take_rows(&targets);
+        self.marked.clear();
+        _ = self.save();
+        rows.into_iter().map(|(_, row)| row).collect()
+    }
+
+    /// Inserts copies of cards below the selected one. Cards keep their IDs
+    /// unless the board already has a card with the same ID.
+    pub fn paste_rows(&mut self, rows: &[Row]) {
+        if rows.is_empty() {
+            return;
+        }
+        let Some(col) = self.columns.get(self.selected_column) else { return };
+        let index = col.state.selected().map_or(col.rows.len(), |i| i + 1);
+        self.snapshot();
+        self.add_rows(self.selected_column, index, rows.to_vec());
+        _ = self.save();
+    }
+
+    /// Adds cards from another board to the top or bottom of a column,
+    /// selecting it
+    pub fn insert_rows(&mut self, column: usize, top: bool, rows: Vec<Row>) {
+        if rows.is_empty() || column >= self.columns.len() {
+            return;
+        }
+        self.snapshot();
+        self.select_column(column);
+        let index = if top {
+            0
+        } else {
+            self.columns[column].rows.len()
+        };
+        self.add_rows(column, index, rows);
+        _ = self.save();
+    }
+
+    /// Adds imported cards to the columns with the same titles, ignoring
+    /// case, creating any columns the board doesn't have yet. Cards with the
+    /// ID of a card on the board replace it, in its place if it's in the
+    /// same column.
+    pub fn import(&mut self, columns: Vec<ImportColumn>) -> color_eyre::Result<()> {
+        for imported in columns {
+            let index = match self.column_index(&imported.title) {
+                Some(index) => index,
+                None => {
+                    self.columns.push(Column::new(imported.title));
+                    self.columns.len() - 1
+                }
+            };
+            let mut added = Vec::new();
+            for mut row in imported.rows {
+                let col = &mut self.columns[index];
+                if let Some(existing) = col.rows.iter_mut().find(|r| r.id != 0 && r.id == row.id) {
+                    row.key = std::mem::take(&mut existing.key);
+                    *existing = row;
                 } else {
-                    Some(index - 1)
+                    self.remove_id(row.id);
+                    added.push(row);
+                }
+            }
+            let end = self.columns[index].rows.len();
+            self.add_rows(index, end, added);
+            for mut row in imported.archived {
+                self.remove_id(row.id);
+                if row.id == 0 || self.has_id(row.id) {
+                    self.renumber(&mut row);
                 }
+                self.next_id = self.next_id.max(row.id);
+                self.columns[index].archived.push(row);
             }
+        }
+        self.select_column(0);
+        self.save()
+    }
+
+    /// Whether a card on the board, archived or not, has this ID
+    pub fn has_id(&self, id: u64) -> bool {
+        self.columns
+            .iter()
+            .any(|col| col.rows.iter().chain(&col.archived).any(|row| row.id == id))
+    }
+
+    /// Takes the card with this ID off the board, archived or not
+    fn remove_id(&mut self, id: u64) {
+        if id == 0 {
+            return;
+        }
+        for col in self.columns.iter_mut() {
+            col.rows.retain(|row| row.id != id);
+            col.archived.retain(|row| row.id != id);
+        }
+    }
+
+    /// Finds a column by its title, ignoring case
+    pub fn column_index(&self, title: &str) -> Option<usize> {
+        self.columns
+            .iter()
+            .position(|col| col.title.eq_ignore_ascii_case(title))
+    }
+
+    /// Inserts cards at `index` in a column and selects the first of them,
+    /// giving new IDs to cards whose IDs are taken
+    fn add_rows(&mut self, column: usize, index: usize, mut rows: Vec<Row>) {
+        if rows.is_empty() {
+            return;
+        }
+        let mut used: HashSet<u64> = self
+            .columns
+            .iter()
+            .flat_map(|col| col.rows.iter().chain(&col.archived).map(|row| row.id))
+            .collect();
+        self.next_id = self
+            .next_id
+            .max(rows.iter().map(|row| row.id).max().unwrap_or(0));
+        for row in rows.iter_mut() {
+            if row.id == 0 || !used.insert(row.id) {
+                self.renumber(row);
+                used.insert(row.id);
+            }
+        }
+
+        let col = &mut self.columns[column];
+        col.rows.splice(index..index, rows);
+        col.state.select(Some(index));
+    }
+
+    pub fn is_marked(&self, id: u64) -> bool {
+        self.marked.contains(&id)
+    }
+
+    pub fn marked_count(&self) -> usize {
+        self.marked.len()
+    }
+
+    pub fn toggle_mark(&mut self) {
+        let Some(id) = self.selected_row().map(|row| row.id) else { return };
+        if !self.marked.remove(&id) {
+            self.marked.insert(id);
+        }
+        self.mark_anchor = Some(id);
+    }
+
+    /// Marks every card from the one last marked up to the selected one,
+    /// reading the board column by column
+    pub fn mark_range(&mut self) {
+        let Some(id) = self.selected_row().map(|row| row.id) else { return };
+        let ids: Vec<u64> = self
+            .columns
+            .iter()
+            .flat_map(|col| col.rows.iter().map(|row| row.id))
+            .collect();
+        let end = ids.iter().position(|&i| i == id).unwrap_or(0);
+        let start = self
+            .mark_anchor
+            .and_then(|anchor| ids.iter().position(|&i| i == anchor))
+            .unwrap_or(end);
+        self.marked.extend(&ids[start.min(end)..=start.max(end)]);
+        self.mark_anchor = Some(id);
+    }
+
+    pub fn clear_marks(&mut self) {
+        self.marked.clear();
+        self.mark_anchor = None;
+    }
+
+    /// The IDs of the cards a bulk operation applies to: the marked cards,
+    /// or the selected one if none are marked
+    pub fn targets(&mut self) -> Vec<u64> {
+        if !self.marked.is_empty() {
+            return self.marked.iter().copied().collect();
+        }
+        self.selected_row().map(|row| row.id).into_iter().collect()
+    }
+
+    fn rows_mut(&mut self) -> impl Iterator<Item = &mut Row> {
+        self.columns.iter_mut().flat_map(|col| col.rows.iter_mut())
+    }
+
+    /// Removes the cards with the given IDs from the board, returning them
+    /// along with the column each was in. Selections are kept in range.
+    fn take_rows(&mut self, ids: &[u64]) -> Vec<(usize, Row)> {
+        let mut taken = Vec::new();
+        for (i, col) in self.columns.iter_mut().enumerate() {
+            let (removed, kept) = std::mem::take(&mut col.rows)
+                .into_iter()
+                .partition(|row| ids.contains(&row.id));
+            col.rows = kept;
+            taken.extend(removed.into_iter().map(|row: Row| (i, row)));
+
+            if let Some(selected) = col.state.selected() {
+                col.state
+                    .select(col.rows.len().checked_sub(1).map(|last| selected.min(last)));
+            }
+        }
+        taken
+    }
+
+    /// Creates a column at `index`, or at the end if `None`, and selects it
+    pub fn create_column(&mut self, title: String, index: Option<usize>) {
+        let index = index.unwrap_or(self.columns.len()).min(self.columns.len());
+        self.snapshot();
+        self.columns.insert(index, Column::new(title));
+        self.select_column(index);
+        _ = self.save();
+    }
+
+    /// Swaps the selected column with its neighbour, keeping it selected
+    pub fn move_column(&mut self, right: bool) {
+        let index = self.selected_column;
+        let other = if right {
+            index + 1
+        } else {
+            let Some(other) = index.checked_sub(1) else { return };
+            other
         };
-        col.state.select(new_selection);
-        _ = col.rows.remove(index);
+        if other >= self.columns.len() {
+            return;
+        }
+        self.snapshot();
+        self.columns.swap(index, other);
+        self.selected_column = other;
         _ = self.save();
     }
 
-    pub fn create_column(&mut self, title: String) {
-        self.columns.push(Column {
-            title,
-            rows: Vec::new(),
-            state: TableState::default(),
-        });
+    /// Inserts a copy of the selected column after it, with copies of its
+    /// cards if `with_cards` is set. Copied cards get new IDs.
+    pub fn duplicate_column(&mut self, with_cards: bool) {
+        if self.selected_column >= self.columns.len() {
+            return;
+        }
+        self.snapshot();
+        let col = &self.columns[self.selected_column];
+        let mut copy = Column::new(format!("{} (copy)", col.title));
+        copy.width = col.width;
+        copy.collapsed = col.collapsed;
+        if with_cards {
+            copy.rows = col.rows.clone();
+            for row in copy.rows.iter_mut() {
+                self.renumber(row);
+            }
+        }
+        let index = self.selected_column + 1;
+        self.columns.insert(index, copy);
+        self.select_column(index);
         _ = self.save();
     }
 
     pub fn update_column(&mut self, title: String) {
-        let Some(col) = self.selected_column() else { return};
+        let Some(col) = self.selected_column() else { return };
         col.title = title;
         _ = self.save();
     }
 
+    /// Grows or shrinks the selected column, starting from the width it was
+    /// last drawn at
+    pub fn resize_column(&mut self, grow: bool) {
+        let Some(col) = self.selected_column() else { return };
+        if col.collapsed {
+            return;
+        }
+        let width = col.width.unwrap_or(col.area.width + 2);
+        col.width = Some(if grow {
+            width.saturating_add(RESIZE_STEP)
+        } else {
+            width.saturating_sub(RESIZE_STEP).max(NARROWEST_COLUMN)
+        });
+        _ = self.save();
+    }
+
+    /// Lets the selected column share the free space again
+    pub fn reset_column_width(&mut self) {
+        let Some(col) = self.selected_column() else { return };
+        col.width = None;
+        _ = self.save();
+    }
+
+    pub fn toggle_collapsed(&mut self) {
+        let Some(col) = self.selected_column() else { return };
+        col.collapsed = !col.collapsed;
+        _ = self.save();
+    }
+
+    pub fn cycle_density(&mut self) {
+        self.density = match self.density {
+            Density::Full => Density::Summary,
+            Density::Summary => Density::Compact,
+            Density::Compact => Density::Full,
+        };
+        _ = self.save();
+    }
+
     pub fn delete_column(&mut self) {
         if self.selected_column >= self.columns.len() {
             return;
         }
+        self.snapshot();
         self.columns.remove(self.selected_column);
         self.select_column(0);
         _ = self.save();
     }
 
     pub fn select_column(&mut self, index: usize) {
-        if self.columns.len() == 0 {
+        if self.columns.is_empty() {
             return;
         }
         self.selected_column = index;
 
         self.columns.iter_mut().enumerate().for_each(|(i, col)| {
-            if i == index && col.rows.len() > 0 {
+            if i == index && !col.rows.is_empty() {
                 col.state.select(Some(0));
             } else {
                 col.state.select(None)
@@ -138,6 +628,9 @@ impl Board {
     }
 
     pub fn right(&mut self, move_row: bool) {
+        if self.columns.is_empty() {
+            return;
+        }
         let index = if self.selected_column >= self.columns.len() - 1 {
             0
         } else {
@@ -155,6 +648,9 @@ impl Board {
     }
 
     pub fn left(&mut self, move_row: bool) {
+        if self.columns.is_empty() {
+            return;
+        }
         let index = if self.selected_column == 0 {
             self.columns.len() - 1
         } else {
@@ -174,7 +670,7 @@ impl Board {
 
     pub fn down(&mut self, move_row: bool) {
         let Some(col) = self.selected_column() else { return };
-        if col.rows.len() == 0 {
+        if col.rows.is_empty() {
             return;
         }
         let (origin, dest) = match col.state.selected() {
@@ -196,7 +692,7 @@ impl Board {
 
     pub fn up(&mut self, move_row: bool) {
         let Some(col) = self.selected_column() else { return };
-        if col.rows.len() == 0 {
+        if col.rows.is_empty() {
             return;
         }
         let (origin, dest) = match col.state.selected() {
@@ -216,6 +712,35 @@ impl Board {
         }
     }
 
+    /// Moves the selection by a page, as many cards as were last in view
+    pub fn page(&mut self, down: bool) {
+        let Some(col) = self.selected_column() else { return };
+        if col.rows.is_empty() {
+            return;
+        }
+        let page = col.row_areas.len().max(1);
+        let selected = col.state.selected().unwrap_or(0);
+        col.state.select(Some(if down {
+            (selected + page).min(col.rows.len() - 1)
+        } else {
+            selected.saturating_sub(page)
+        }));
+    }
+
+    pub fn first(&mut self) {
+        let Some(col) = self.selected_column() else { return };
+        if !col.rows.is_empty() {
+            col.state.select(Some(0));
+        }
+    }
+
+    pub fn last(&mut self) {
+        let Some(col) = self.selected_column() else { return };
+        if !col.rows.is_empty() {
+            col.state.select(Some(col.rows.len() - 1));
+        }
+    }
+
     pub fn move_row(&mut self, origin: (usize, usize), destination: (usize, usize)) {
         let popped = self.columns[origin.0].rows.remove(origin.1);
         self.columns[destination.0]
@@ -225,26 +750,116 @@ impl Board {
         self.selected_column = destination.0;
 
         self.columns.iter_mut().enumerate().for_each(|(i, col)| {
-            if i == destination.0 && col.rows.len() > 0 {
+            if i == destination.0 && !col.rows.is_empty() {
                 col.state.select(Some(destination.1));
             } else {
                 col.state.select(None)
             }
         });
-        _ = self.save().expect("Failed to write to file");
+        self.save().expect("Failed to write to file");
     }
 
-    pub fn from_file(file: String, file_name: String) -> color_eyre::Result<Self> {
-        let mut board: Board = serde_json::from_str(&file)?;
+    /// Reads the board stored at `filename`, in a file or a directory
+    pub fn load(filename: &str) -> color_eyre::Result<Self> {
+        let mut board = storage(filename).load(filename)?;
+        board.assign_ids();
         board.select_column(0);
-        board.filename = file_name;
+        board.filename = filename.to_string();
         Ok(board)
     }
 
+    /// Stores the board at `filename` from now on, in the way its name
+    /// calls for
+    pub fn save_as(&mut self, filename: &str) -> color_eyre::Result<()> {
+        self.filename = filename.to_string();
+        self.save()
+    }
+
+    /// When the board's file or directory was last changed
+    pub fn modified(&self) -> Option<SystemTime> {
+        storage(&self.filename).modified(&self.filename)
+    }
+
+    fn next_id(&mut self) -> u64 {
+        self.next_id += 1;
+        self.next_id
+    }
+
+    /// Gives a copy of a card an ID and file name of its own
+    fn renumber(&mut self, row: &mut Row) {
+        row.id = self.next_id();
+        row.key = card_key();
+    }
+
+    /// Gives an ID to any card without one, e.g. from boards saved before
+    /// cards had IDs
+    fn assign_ids(&mut self) {
+        let max_id = self
+            .columns
+            .iter()
+            .flat_map(|col| col.rows.iter().map(|row| row.id))
+            .max()
+            .unwrap_or(0);
+        self.next_id = self.next_id.max(max_id);
+        for row in self.columns.iter_mut().flat_map(|col| col.rows.iter_mut()) {
+            if row.id == 0 {
+                self.next_id += 1;
+                row.id = self.next_id;
+            }
+        }
+    }
+
+    /// Remembers the columns as they are, so that the change about to be
+    /// made can be undone
+    fn snapshot(&mut self) {
+        if self.undo.len() == UNDO_LIMIT {
+            self.undo.remove(0);
+        }
+        self.undo.push((self.columns.clone(), self.selected_column));
+    }
+
+    /// Puts the columns back as they were before the last bulk or column
+    /// change, returning whether there was one to undo
+    pub fn undo(&mut self) -> bool {
+        let Some((columns, selected_column)) = self.undo.pop() else { return false };
+        self.columns = columns;
+        self.selected_column = selected_column;
+        _ = self.save();
+        true
+    }
+
     fn save(&self) -> color_eyre::Result<()> {
-        let serialized = serde_json::to_string(self)?;
-        fs::write(&self.filename, serialized)?;
-        Ok(())
+        storage(&self.filename).save(self, &self.filename)
+    }
+}
+
+pub fn contains(rect: Rect, x: u16, y: u16) -> bool {
+    x >= rect.left() && x < rect.right() && y >= rect.top() && y < rect.bottom()
+}
+
+/// Whether cards in a column with this title count as finished
+pub fn is_done_column(title: &str) -> bool {
+    matches!(
+        title.to_lowercase().as_str(),
+        "done" | "complete" | "completed" | "finished" | "closed"
+    )
+}
+
+/// How much of each card is shown on the board
+#[derive(Serialize, Deserialize, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Density {
+    /// Titles only
+    Compact,
+    /// Titles and the first line of descriptions
+    Summary,
+    #[default]
+    Full,
+}
+
+impl Density {
+    fn is_full(&self) -> bool {
+        *self == Density::Full
     }
 }
 
@@ -261,26 +876,149 @@ impl From<TableStateDef> for TableState {
     }
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct Column {
     pub title: String,
     pub rows: Vec<Row>,
+    /// Fixed width of the column, including its borders
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub width: Option<u16>,
+    /// Collapsed columns are drawn as a thin strip
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub collapsed: bool,
+    /// Cards taken off the board, kept in the file but not shown
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub archived: Vec<Row>,
     #[serde(default, skip_serializing, with = "TableStateDef")]
     pub state: TableState,
+    /// Index of the first card in view
+    #[serde(skip)]
+    pub offset: usize,
+    /// Where the column was last drawn, for mouse clicks
+    #[serde(skip)]
+    pub area: Rect,
+    /// Where each visible card was last drawn, by index
+    #[serde(skip)]
+    pub row_areas: Vec<(usize, Rect)>,
 }
 
 impl Column {
-    fn new(title: String) -> Self {
+    /// The width the column takes up, if it doesn't share the free space
+    pub fn fixed_width(&self) -> Option<u16> {
+        if self.collapsed {
+            Some(COLLAPSED_WIDTH)
+        } else {
+            self.width
+        }
+    }
+
+    /// Whether the cards in the column count as finished, going by its title
+    pub fn is_done(&self) -> bool {
+        is_done_column(&self.title)
+    }
+
+    pub(super) fn new(title: String) -> Self {
         Column {
             title,
             rows: Vec::new(),
+            width: None,
+            collapsed: false,
+            archived: Vec::new(),
             state: TableState::default(),
+            offset: 0,
+            area: Rect::default(),
+            row_areas: Vec::new(),
         }
     }
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct Row {
+    #[serde(default)]
+    pub id: u64,
+    /// Names the card's file when the board is a directory. IDs are handed
+    /// out in order, so cards added on two branches can get the same one,
+    /// but never the same key.
+    #[serde(skip, default = "card_key")]
+    pub key: String,
     pub title: String,
     pub description: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub labels: Vec<String>,
+    /// A letter from `A` (highest) to `Z`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<char>,
+    /// The date the card is due, as `YYYY-MM-DD`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub due: Option<String>,
+}
+
+impl Row {
+    /// A card without an ID yet, one is given when it's added to a board
+    pub fn new(title: String) -> Self {
+        Row {
+            id: 0,
+            key: card_key(),
+            title,
+            description: String::new(),
+            labels: Vec::new(),
+            priority: None,
+            due: None,
+        }
+    }
+}
+
+/// A new key for a card: the time, so that keys sort oldest first, and a
+/// random part for cards made at the same time
+fn card_key() -> String {
+    let time = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u128(time.as_nanos());
+    format!("{:011x}-{:08x}", time.as_millis(), hasher.finish() as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn titles_untitled_boards_after_their_file() {
+        for (filename, title) in [
+            (".kb/x.json", "x"),
+            (".kb/my_board.yaml", "my board"),
+            (".kb/_x_.toml", "x"),
+            (".kb/cards", "cards"),
+            ("kanban.json", "kanban"),
+        ] {
+            assert_eq!(Board::new(None, filename).title(), title);
+        }
+        let board = Board::new(Some("Plans".to_string()), ".kb/x.json");
+        assert_eq!(board.title(), "Plans");
+    }
+
+    #[test]
+    fn pasting_nothing_leaves_nothing_to_undo() {
+        let mut board = Board::new(None, "board.json");
+        board.paste_rows(&[]);
+        assert!(!board.undo());
+    }
+
+    #[test]
+    fn editing_no_cards_leaves_nothing_to_undo() {
+        let mut board = Board::new(None, "board.json");
+        board.edit_labels(&["bug".to_string()]);
+        board.set_priority(Some('A'));
+        assert!(!board.undo());
+    }
+
+    #[test]
+    fn moves_between_columns_of_a_board_without_any() {
+        let mut board = Board::new(None, "board.json");
+        board.columns.clear();
+        board.right(false);
+        board.left(true);
+        assert_eq!(board.selected_column, 0);
+    }
 }
@@ -0,0 +1,10 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use std::io::{self, Write};
+
+/// Puts text on the system clipboard using the OSC 52 escape sequence, which
+/// the terminal passes on, even over SSH
+pub fn copy_to_system(text: &str) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    write!(stdout, "\x1b]52;c;{}\x07", STANDARD.encode(text))?;
+    stdout.flush()
+}
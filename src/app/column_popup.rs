@@ -11,6 +11,8 @@ pub enum ColumnFields {
 
 pub struct ColumnPopupState<'a> {
     pub title: TextArea<'a>,
+    /// Where a new column goes, at the end if `None`
+    pub index: Option<usize>,
 }
 
 impl PopupFields for ColumnFields {
@@ -31,10 +33,19 @@ impl<'a> ColumnPopupState<'a> {
     pub fn new(title: &str) -> Self {
         let mut new = Self {
             title: title.lines().map(|s| s.to_string()).collect(),
+            index: None,
         };
         new.title.move_cursor(CursorMove::End);
         new
     }
+    /// A popup creating a column at `index`
+    pub fn insert(index: usize) -> Self {
+        Self {
+            index: Some(index),
+            ..Self::new("")
+        }
+    }
+
     pub fn on_keypress(&mut self, key: KeyEvent) {
         self.title.input(key);
     }
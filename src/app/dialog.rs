@@ -1,8 +1,9 @@
 use crossterm::event::{KeyCode, KeyEvent};
+use tui::layout::Rect;
 
-use super::PopupFields;
+use super::{board::contains, PopupFields};
 
-#[derive(Default, PartialEq)]
+#[derive(Default, PartialEq, Clone, Copy)]
 pub enum DialogFields {
     #[default]
     Confirm,
@@ -25,6 +26,9 @@ impl PopupFields for DialogFields {
 pub struct DialogState {
     pub focussed: DialogFields,
     pub message: String,
+    /// Where each button was last drawn, for mouse clicks
+    pub confirm_area: Rect,
+    pub cancel_area: Rect,
 }
 
 impl DialogState {
@@ -32,6 +36,18 @@ impl DialogState {
         Self {
             message: message.to_string(),
             focussed: DialogFields::default(),
+            confirm_area: Rect::default(),
+            cancel_area: Rect::default(),
+        }
+    }
+
+    pub fn button_at(&self, x: u16, y: u16) -> Option<DialogFields> {
+        if contains(self.confirm_area, x, y) {
+            Some(DialogFields::Confirm)
+        } else if contains(self.cancel_area, x, y) {
+            Some(DialogFields::Cancel)
+        } else {
+            None
         }
     }
     pub fn cycle_focus(&mut self) {
@@ -1,29 +1,34 @@
-#![allow(dead_code)]
 use crossterm::event::{KeyCode, KeyEvent};
 use tui::widgets::TableState;
 
+use super::storage::is_board;
+
 pub struct FilePickerState {
     pub files: Vec<String>,
     pub state: TableState,
 }
 
 impl FilePickerState {
-
     pub fn new(dir: &str) -> Self {
         let files: Vec<String> = match std::fs::read_dir(dir) {
-            Ok(dir) => dir
+            Ok(entries) => entries
                 .filter_map(|f| f.ok())
                 .filter_map(|f| f.file_name().into_string().ok())
-                .filter(|f| f.ends_with(".json"))
+                .filter(|f| is_board(&format!("{}/{}", dir, f)))
                 .collect(),
             Err(_) => Vec::new(),
         };
-        Self {
-            files,
-            state: TableState::default(),
-        }    
+        let mut state = TableState::default();
+        if !files.is_empty() {
+            state.select(Some(0));
+        }
+        Self { files, state }
     }
-    
+
+    pub fn selected(&self) -> Option<&str> {
+        self.state.selected().map(|i| self.files[i].as_str())
+    }
+
     pub fn on_keypress(&mut self, key: KeyEvent) {
         let Some(selected) = self.state.selected() else {
             if !self.files.is_empty() {
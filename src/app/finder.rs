@@ -0,0 +1,136 @@
+use crossterm::event::KeyEvent;
+use std::{fs, path::Path};
+
+use super::{
+    board::Board,
+    fuzzy,
+    fuzzy_list::{FuzzyEntry, FuzzyList, SearchField},
+    storage::is_board,
+};
+
+/// A card indexed by the finder, along with where to find it.
+pub struct FinderEntry {
+    pub filename: String,
+    pub board: String,
+    pub column: String,
+    pub id: u64,
+    pub title: String,
+    pub description: String,
+}
+
+impl FuzzyEntry for FinderEntry {
+    fn score(&self, query: &str) -> Option<i64> {
+        [
+            fuzzy::score(query, &self.title).map(|score| score * 2),
+            fuzzy::score(query, &self.description),
+            fuzzy::score(query, &format!("#{}", self.id)),
+        ]
+        .into_iter()
+        .flatten()
+        .max()
+    }
+}
+
+pub struct FinderState<'a> {
+    pub list: FuzzyList<'a, FinderEntry>,
+}
+
+impl<'a> FinderState<'a> {
+    /// Indexes every card in `current` and in the boards stored in `dir`.
+    pub fn new(dir: &str, current: &Board) -> Self {
+        let mut entries = Vec::new();
+        index_board(current, &mut entries);
+
+        if let Ok(dir) = fs::read_dir(dir) {
+            let mut files: Vec<String> = dir
+                .filter_map(|f| f.ok())
+                .map(|f| f.path())
+                .filter_map(|path| path.to_str().map(|s| s.to_string()))
+                .filter(|path| is_board(path))
+                .filter(|path| Path::new(path) != Path::new(current.filename()))
+                .collect();
+            files.sort();
+            for filename in files {
+                let Ok(board) = Board::load(&filename) else { continue };
+                index_board(&board, &mut entries);
+            }
+        }
+
+        let field = SearchField {
+            title: "Search",
+            placeholder: "Search cards in every board...",
+        };
+        Self {
+            list: FuzzyList::new(field, entries),
+        }
+    }
+
+    pub fn selected(&self) -> Option<&FinderEntry> {
+        self.list.selected()
+    }
+
+    pub fn on_keypress(&mut self, key: KeyEvent) {
+        self.list.on_keypress(key);
+    }
+}
+
+fn index_board(board: &Board, entries: &mut Vec<FinderEntry>) {
+    for col in &board.columns {
+        for row in &col.rows {
+            entries.push(FinderEntry {
+                filename: board.filename().to_string(),
+                board: board.title(),
+                column: col.title.clone(),
+                id: row.id,
+                title: row.title.clone(),
+                description: row.description.clone(),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    fn entry(id: u64, title: &str, description: &str) -> FinderEntry {
+        FinderEntry {
+            filename: "board.json".to_string(),
+            board: "Board".to_string(),
+            column: "To Do".to_string(),
+            id,
+            title: title.to_string(),
+            description: description.to_string(),
+        }
+    }
+
+    /// The IDs of the cards matching `query`, best match first
+    fn search(entries: Vec<FinderEntry>, query: &str) -> Vec<u64> {
+        let field = SearchField {
+            title: "Search",
+            placeholder: "",
+        };
+        let mut list = FuzzyList::new(field, entries);
+        for c in query.chars() {
+            list.on_keypress(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+        list.matches().map(|entry| entry.id).collect()
+    }
+
+    #[test]
+    fn ranks_title_matches_above_description_matches() {
+        let entries = vec![
+            entry(1, "Write docs", "Mention the login page"),
+            entry(2, "Fix login", ""),
+            entry(3, "Release", ""),
+        ];
+        assert_eq!(search(entries, "login"), [2, 1]);
+    }
+
+    #[test]
+    fn finds_cards_by_id() {
+        let entries = vec![entry(3, "Fix login", ""), entry(12, "Write docs", "")];
+        assert_eq!(search(entries, "#12"), [12]);
+    }
+}
@@ -0,0 +1,52 @@
+/// Scores how well `pattern` fuzzy-matches `text`, ignoring case.
+///
+/// Every non-whitespace character of the pattern has to appear in the text in
+/// order. Consecutive matches and matches at the start of a word score higher,
+/// gaps between matches score lower. Returns `None` if the text doesn't match.
+pub fn score(pattern: &str, text: &str) -> Option<i64> {
+    let text: Vec<char> = text.chars().flat_map(char::to_lowercase).collect();
+    let mut score = 0;
+    let mut position = 0;
+    let mut last_match: Option<usize> = None;
+
+    for c in pattern
+        .chars()
+        .flat_map(char::to_lowercase)
+        .filter(|c| !c.is_whitespace())
+    {
+        let index = position + text[position..].iter().position(|&t| t == c)?;
+        score += 1;
+        match last_match {
+            Some(last) if last + 1 == index => score += 5,
+            Some(last) => score -= (index - last - 1).min(5) as i64,
+            None => {}
+        }
+        if index == 0 || !text[index - 1].is_alphanumeric() {
+            score += 8;
+        }
+        last_match = Some(index);
+        position = index + 1;
+    }
+    Some(score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_characters_in_order_ignoring_case_and_spaces() {
+        assert!(score("FL", "fix login").is_some());
+        assert_eq!(score("f l", "fix login"), score("fl", "fix login"));
+        assert_eq!(score("lf", "fix login"), None);
+        assert_eq!(score("", "anything"), Some(0));
+        assert_eq!(score("x", ""), None);
+    }
+
+    #[test]
+    fn prefers_consecutive_matches_and_word_starts() {
+        assert!(score("log", "login") > score("log", "a long dog"));
+        assert!(score("fl", "fix login") > score("fl", "fulfil"));
+        assert!(score("doc", "write docs") > score("doc", "undocumented"));
+    }
+}
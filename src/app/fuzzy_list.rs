@@ -0,0 +1,136 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::cmp::Reverse;
+use tui::widgets::TableState;
+use tui_textarea::TextArea;
+
+use super::PopupFields;
+
+/// Something listed by a `FuzzyList`
+pub trait FuzzyEntry {
+    /// How well the entry matches `query`, higher is better. Returns `None`
+    /// if it doesn't match.
+    fn score(&self, query: &str) -> Option<i64>;
+}
+
+/// The text field a fuzzy list is searched with
+#[derive(Clone, Copy)]
+pub struct SearchField {
+    pub title: &'static str,
+    pub placeholder: &'static str,
+}
+
+impl PopupFields for SearchField {
+    fn title(&self) -> &str {
+        self.title
+    }
+
+    fn placeholder(&self) -> &str {
+        self.placeholder
+    }
+}
+
+/// A list of entries filtered by a query as it's typed, best match first,
+/// with one of the matches selected
+pub struct FuzzyList<'a, T> {
+    pub field: SearchField,
+    pub query: TextArea<'a>,
+    entries: Vec<T>,
+    /// Indices into `entries`, best match first
+    matches: Vec<usize>,
+    pub state: TableState,
+}
+
+impl<'a, T: FuzzyEntry> FuzzyList<'a, T> {
+    pub fn new(field: SearchField, entries: Vec<T>) -> Self {
+        let mut new = Self {
+            field,
+            query: TextArea::default(),
+            entries,
+            matches: Vec::new(),
+            state: TableState::default(),
+        };
+        new.update_matches();
+        new
+    }
+
+    /// Every entry, whether it matches or not
+    pub fn entries(&self) -> &[T] {
+        &self.entries
+    }
+
+    /// The entries matching the query, best match first
+    pub fn matches(&self) -> impl Iterator<Item = &T> {
+        self.matches.iter().map(|&i| &self.entries[i])
+    }
+
+    pub fn selected(&self) -> Option<&T> {
+        let index = self.state.selected()?;
+        self.matches.get(index).map(|&i| &self.entries[i])
+    }
+
+    pub fn on_keypress(&mut self, key: KeyEvent) {
+        match key {
+            KeyEvent {
+                code: KeyCode::Down,
+                ..
+            }
+            | KeyEvent {
+                code: KeyCode::Char('n'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            } => self.down(),
+            KeyEvent {
+                code: KeyCode::Up, ..
+            }
+            | KeyEvent {
+                code: KeyCode::Char('p'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            } => self.up(),
+            KeyEvent {
+                code: KeyCode::Enter,
+                ..
+            } => {}
+            _ => {
+                if self.query.input(key) {
+                    self.update_matches();
+                }
+            }
+        }
+    }
+
+    fn update_matches(&mut self) {
+        let query = self.query.lines().join("");
+        let mut scored: Vec<(usize, i64)> = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter_map(|(i, entry)| entry.score(&query).map(|score| (i, score)))
+            .collect();
+        scored.sort_by_key(|&(_, score)| Reverse(score));
+        self.matches = scored.into_iter().map(|(i, _)| i).collect();
+        self.state.select(if self.matches.is_empty() {
+            None
+        } else {
+            Some(0)
+        });
+    }
+
+    fn down(&mut self) {
+        let Some(selected) = self.state.selected() else { return };
+        self.state.select(Some(if selected + 1 >= self.matches.len() {
+            0
+        } else {
+            selected + 1
+        }));
+    }
+
+    fn up(&mut self) {
+        let Some(selected) = self.state.selected() else { return };
+        self.state.select(Some(if selected == 0 {
+            self.matches.len() - 1
+        } else {
+            selected - 1
+        }));
+    }
+}
@@ -0,0 +1,345 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+use super::Action;
+
+/// A key along with the modifiers held down while pressing it
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct KeyChord {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyChord {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        Self { code, modifiers }
+    }
+
+    pub fn matches(&self, key: &KeyEvent) -> bool {
+        if key.code != self.code {
+            return false;
+        }
+        match key.code {
+            // Shift is already reflected in the case of the character, and
+            // in back tab being a key of its own
+            KeyCode::Char(_) | KeyCode::BackTab => {
+                key.modifiers - KeyModifiers::SHIFT == self.modifiers - KeyModifiers::SHIFT
+            }
+            _ => key.modifiers == self.modifiers,
+        }
+    }
+}
+
+impl From<KeyCode> for KeyChord {
+    fn from(code: KeyCode) -> Self {
+        Self::new(code, KeyModifiers::NONE)
+    }
+}
+
+/// Parses chords such as `c`, `C`, `shift-left`, `ctrl-p` or `pagedown`
+impl FromStr for KeyChord {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut key = s;
+        // A trailing `-` or `+` is the key itself, not a separator
+        while let Some(index) = key[..last_char(key)].find(['-', '+']) {
+            modifiers |= match key[..index].to_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" | "meta" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                other => return Err(format!("unknown modifier `{}` in `{}`", other, s)),
+            };
+            key = &key[index + 1..];
+        }
+
+        let mut chars = key.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => {
+                if modifiers.contains(KeyModifiers::SHIFT) {
+                    modifiers -= KeyModifiers::SHIFT;
+                    KeyCode::Char(uppercase(c))
+                } else {
+                    KeyCode::Char(c)
+                }
+            }
+            _ => match key.to_lowercase().as_str() {
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "enter" | "return" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "backspace" => KeyCode::Backspace,
+                "tab" if modifiers.contains(KeyModifiers::SHIFT) => {
+                    modifiers -= KeyModifiers::SHIFT;
+                    KeyCode::BackTab
+                }
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "space" => KeyCode::Char(' '),
+                "delete" | "del" => KeyCode::Delete,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                other => match other.strip_prefix('f').and_then(|n| n.parse().ok()) {
+                    Some(n) if (1..=12).contains(&n) => KeyCode::F(n),
+                    _ => return Err(format!("unknown key `{}`", s)),
+                },
+            },
+        };
+        Ok(Self::new(code, modifiers))
+    }
+}
+
+/// Where the last character of `s` starts
+fn last_char(s: &str) -> usize {
+    s.char_indices().last().map_or(0, |(index, _)| index)
+}
+
+/// The uppercase of a character, or the character itself if its uppercase
+/// takes more than one character, as `ß` does
+fn uppercase(c: char) -> char {
+    let mut upper = c.to_uppercase();
+    match (upper.next(), upper.next()) {
+        (Some(upper), None) => upper,
+        _ => c,
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "^")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "⌥")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) && !matches!(self.code, KeyCode::Char(_)) {
+            write!(f, "⇧")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) if c.is_uppercase() => write!(f, "⇧{}", c.to_lowercase()),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::Up => write!(f, "↑"),
+            KeyCode::Down => write!(f, "↓"),
+            KeyCode::Left => write!(f, "←"),
+            KeyCode::Right => write!(f, "→"),
+            KeyCode::Enter => write!(f, "↵"),
+            KeyCode::Esc => write!(f, "Esc"),
+            KeyCode::Backspace => write!(f, "⌫"),
+            KeyCode::Tab => write!(f, "Tab"),
+            KeyCode::BackTab => write!(f, "⇧Tab"),
+            KeyCode::Delete => write!(f, "Del"),
+            KeyCode::Home => write!(f, "Home"),
+            KeyCode::End => write!(f, "End"),
+            KeyCode::PageUp => write!(f, "PgUp"),
+            KeyCode::PageDown => write!(f, "PgDn"),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            _ => write!(f, "?"),
+        }
+    }
+}
+
+/// Maps keys pressed on the board to actions
+pub struct Keymap {
+    bindings: Vec<(KeyChord, Action)>,
+}
+
+impl Keymap {
+    /// Builds a keymap from the defaults, replacing the keys of every action
+    /// named in `overrides`. All problems are collected rather than stopping
+    /// at the first one.
+    pub fn with_overrides(overrides: &BTreeMap<String, Vec<String>>) -> Result<Self, Vec<String>> {
+        let mut errors = Vec::new();
+        let mut keymap = Self::default();
+
+        for (name, keys) in overrides {
+            let Some(action) = Action::from_name(name) else {
+                errors.push(format!("unknown action `{}`", name));
+                continue;
+            };
+            keymap.bindings.retain(|(_, a)| *a != action);
+            for key in keys {
+                match key.parse::<KeyChord>() {
+                    Ok(chord) => keymap.bindings.push((chord, action)),
+                    Err(err) => errors.push(format!("{}: {}", name, err)),
+                }
+            }
+        }
+
+        for (i, (chord, action)) in keymap.bindings.iter().enumerate() {
+            let conflict = keymap.bindings[..i]
+                .iter()
+                .find(|(other, other_action)| other == chord && other_action != action);
+            if let Some((_, other_action)) = conflict {
+                errors.push(format!(
+                    "`{}` is bound to both {} and {}",
+                    chord,
+                    other_action.name(),
+                    action.name()
+                ));
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(keymap)
+        } else {
+            Err(errors)
+        }
+    }
+
+    pub fn action(&self, key: &KeyEvent) -> Option<Action> {
+        self.bindings
+            .iter()
+            .find(|(chord, _)| chord.matches(key))
+            .map(|(_, action)| *action)
+    }
+
+    pub fn chords(&self, action: Action) -> impl Iterator<Item = &KeyChord> {
+        self.bindings
+            .iter()
+            .filter(move |(_, a)| *a == action)
+            .map(|(chord, _)| chord)
+    }
+
+    /// The keys bound to an action, formatted for display
+    pub fn keys(&self, action: Action) -> String {
+        self.chords(action)
+            .map(|chord| chord.to_string())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        use KeyCode::*;
+        let shift = |code| KeyChord::new(code, KeyModifiers::SHIFT);
+        let ctrl = |code| KeyChord::new(code, KeyModifiers::CONTROL);
+        let alt = |code| KeyChord::new(code, KeyModifiers::ALT);
+        Self {
+            bindings: vec![
+                (Up.into(), Action::Up),
+                (Char('k').into(), Action::Up),
+                (Down.into(), Action::Down),
+                (Char('j').into(), Action::Down),
+                (Left.into(), Action::Left),
+                (Char('h').into(), Action::Left),
+                (Right.into(), Action::Right),
+                (Char('l').into(), Action::Right),
+                (shift(Up), Action::MoveUp),
+                (shift(Down), Action::MoveDown),
+                (shift(Left), Action::MoveLeft),
+                (shift(Right), Action::MoveRight),
+                (Char('m').into(), Action::MoveTo),
+                (Char('1').into(), Action::SelectColumn(0)),
+                (Char('2').into(), Action::SelectColumn(1)),
+                (Char('3').into(), Action::SelectColumn(2)),
+                (Char('4').into(), Action::SelectColumn(3)),
+                (Char('5').into(), Action::SelectColumn(4)),
+                (Char('6').into(), Action::SelectColumn(5)),
+                (Char('7').into(), Action::SelectColumn(6)),
+                (Char('8').into(), Action::SelectColumn(7)),
+                (Char('9').into(), Action::SelectColumn(8)),
+                (PageUp.into(), Action::PageUp),
+                (ctrl(Char('u')), Action::PageUp),
+                (PageDown.into(), Action::PageDown),
+                (ctrl(Char('d')), Action::PageDown),
+                (Home.into(), Action::First),
+                (Char('g').into(), Action::First),
+                (End.into(), Action::Last),
+                (Char('G').into(), Action::Last),
+                (Char('c').into(), Action::CreateRow),
+                (Char('e').into(), Action::EditRow),
+                (Enter.into(), Action::EditRow),
+                (Char('d').into(), Action::DeleteRow),
+                (Backspace.into(), Action::DeleteRow),
+                (Char(' ').into(), Action::ToggleMark),
+                (Char('V').into(), Action::MarkRange),
+                (Char('u').into(), Action::ClearMarks),
+                (Char('M').into(), Action::MoveMarked),
+                (Char('a').into(), Action::ArchiveRows),
+                (Char('A').into(), Action::ShowArchive),
+                (Char('t').into(), Action::EditLabels),
+                (Char('!').into(), Action::SetPriority),
+                (Char('y').into(), Action::Copy),
+                (Char('x').into(), Action::Cut),
+                (Char('p').into(), Action::Paste),
+                (ctrl(Char('z')), Action::Undo),
+                (Char('C').into(), Action::CreateColumn),
+                (Char('O').into(), Action::InsertColumnBefore),
+                (Char('o').into(), Action::InsertColumnAfter),
+                (Char('E').into(), Action::EditColumn),
+                (Char('D').into(), Action::DeleteColumn),
+                (alt(Left), Action::MoveColumnLeft),
+                (alt(Right), Action::MoveColumnRight),
+                (Char('Y').into(), Action::DuplicateColumn),
+                (Char('>').into(), Action::WidenColumn),
+                (Char('<').into(), Action::NarrowColumn),
+                (Char('=').into(), Action::ResetColumnWidth),
+                (Char('z').into(), Action::ToggleCollapsed),
+                (Char('v').into(), Action::CycleDensity),
+                (ctrl(Char('p')), Action::FindCard),
+                (Char('b').into(), Action::SwitchBoard),
+                (Tab.into(), Action::NextTab),
+                (BackTab.into(), Action::PreviousTab),
+                (ctrl(Char('w')), Action::CloseTab),
+                (Char(':').into(), Action::CommandPalette),
+                (Esc.into(), Action::Help),
+                (Char('q').into(), Action::Quit),
+            ],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> KeyChord {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn parses_non_ascii_keys() {
+        assert_eq!(parse("ö"), KeyChord::from(KeyCode::Char('ö')));
+        assert_eq!(
+            parse("ctrl-ö"),
+            KeyChord::new(KeyCode::Char('ö'), KeyModifiers::CONTROL)
+        );
+    }
+
+    #[test]
+    fn uppercases_shifted_non_ascii_keys() {
+        assert_eq!(parse("shift-ä"), KeyChord::from(KeyCode::Char('Ä')));
+    }
+
+    #[test]
+    fn parses_trailing_separator_as_the_key() {
+        assert_eq!(
+            parse("ctrl--"),
+            KeyChord::new(KeyCode::Char('-'), KeyModifiers::CONTROL)
+        );
+    }
+
+    #[test]
+    fn accepts_the_readme_example() {
+        let overrides = BTreeMap::from([
+            (
+                "create_item".to_string(),
+                vec!["n".to_string(), "i".to_string()],
+            ),
+            ("delete_item".to_string(), vec!["X".to_string()]),
+            (
+                "left".to_string(),
+                vec!["left".to_string(), "ctrl-b".to_string()],
+            ),
+            ("quit".to_string(), vec![]),
+        ]);
+        assert!(Keymap::with_overrides(&overrides).is_ok());
+    }
+}
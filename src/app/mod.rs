@@ -1,12 +1,30 @@
+mod action;
+mod archive;
 pub mod args;
 pub mod board;
+mod clipboard;
 mod column_popup;
 mod dialog;
 mod file_picker;
+mod finder;
+mod fuzzy;
+mod fuzzy_list;
+mod keymap;
 pub mod model;
+mod move_to;
+mod palette;
+mod prompt;
 mod row_popup;
+pub mod storage;
 
+pub use action::Action;
+pub use archive::ArchiveState;
 pub use column_popup::{ColumnFields, ColumnPopupState};
 pub use dialog::{DialogFields, DialogState};
 pub use file_picker::FilePickerState;
+pub use finder::FinderState;
+pub use keymap::Keymap;
+pub use move_to::MoveToState;
+pub use palette::PaletteState;
+pub use prompt::{PromptFields, PromptState};
 pub use row_popup::{PopupFields, RowFields, RowPopupState};
@@ -1,7 +1,23 @@
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use std::{
+    fs,
+    path::Path,
+    time::{Duration, Instant},
+};
+use tui::layout::Rect;
+
+use crate::formats::{
+    markdown::{self, MarkdownOptions},
+    Selection,
+};
 
 use super::{
-    board::Board, dialog::DialogState, row_popup::RowPopupState, ColumnPopupState, DialogFields,
+    board::{contains, Board, Row, BOARD_DIR},
+    clipboard,
+    dialog::DialogState,
+    row_popup::RowPopupState,
+    Action, ArchiveState, ColumnPopupState, DialogFields, FilePickerState, FinderState, Keymap,
+    MoveToState, PaletteState, PromptFields, PromptState,
 };
 
 pub enum Popup<'a> {
@@ -12,70 +28,157 @@ pub enum Popup<'a> {
     CreateColumn(ColumnPopupState<'a>),
     EditColumn(ColumnPopupState<'a>),
     DeleteColumn(DialogState),
-    Help,
-    // FilePicker(FilePickerState),
+    /// Help, scrolled down by a number of lines
+    Help(u16),
+    Finder(FinderState<'a>),
+    Palette(PaletteState<'a>),
+    FilePicker(FilePickerState),
+    Prompt(PromptState<'a>),
+    MoveTo(MoveToState<'a>),
+    Archive(ArchiveState<'a>),
 }
 
+/// Two clicks on the same card within this time open it
+const DOUBLE_CLICK: Duration = Duration::from_millis(400);
+
 pub struct Model<'a> {
-    pub board: Board,
+    /// The boards open in tabs, there is always at least one
+    pub boards: Vec<Board>,
+    /// Index of the board being shown
+    pub tab: usize,
+    /// Where each tab was last drawn, for mouse clicks
+    pub tab_areas: Vec<Rect>,
     pub popup: Popup<'a>,
+    pub keymap: Keymap,
     pub quit: bool,
+    /// Shown in the status bar until the next key press
+    pub message: Option<String>,
+    /// Cards copied or cut, kept when switching boards
+    clipboard: Vec<Row>,
+    /// Whether copied cards also go to the system clipboard, as Markdown
+    pub system_clipboard: bool,
+    /// The card being dragged with the mouse, as (column, row)
+    drag: Option<(usize, usize)>,
+    last_click: Option<(Instant, (usize, usize))>,
 }
 
 impl<'a> Model<'a> {
-    pub fn new(board: Board) -> Self {
-        let empty_board = board.columns.is_empty();
+    pub fn new(boards: Vec<Board>, keymap: Keymap) -> Self {
+        let empty_board = boards[0].columns.is_empty();
         Model {
-            board,
+            boards,
+            tab: 0,
+            tab_areas: Vec::new(),
             popup: if empty_board {
                 Popup::CreateColumn(ColumnPopupState::new(""))
             } else {
                 Popup::None
             },
+            keymap,
             quit: false,
+            message: None,
+            clipboard: Vec::new(),
+            system_clipboard: false,
+            drag: None,
+            last_click: None,
         }
     }
 
+    /// The board in the current tab
+    pub fn board(&mut self) -> &mut Board {
+        &mut self.boards[self.tab]
+    }
+
     pub fn edit_item(&mut self) {
-        let Some(row) = self.board.selected_row() else { return };
+        let Some(row) = self.board().selected_row() else { return };
         self.popup = Popup::EditRow(RowPopupState::new(&row.title, &row.description));
     }
 
     pub fn edit_column(&mut self) {
-        let Some(col) = self.board.selected_column() else { return };
+        let Some(col) = self.board().selected_column() else { return };
         self.popup = Popup::EditColumn(ColumnPopupState::new(&col.title));
     }
 
     fn open_delete_dialog(&mut self) {
-        if self.board.selected_row().is_none() {
+        let message = match self.board().targets().len() {
+            0 => return,
+            1 => "Delete Item?".to_string(),
+            count => format!("Delete {} Items?", count),
+        };
+        self.popup = Popup::DeleteRow(DialogState::new(&message));
+    }
+
+    fn open_prompt(&mut self, field: PromptFields) {
+        if self.board().targets().is_empty() {
+            return;
+        }
+        self.popup = Popup::Prompt(PromptState::new(field));
+    }
+
+    /// Applies the value entered in a prompt, keeping the prompt open if
+    /// the value isn't valid
+    fn submit_prompt(&mut self, field: PromptFields, value: &str) {
+        match field {
+            PromptFields::Labels => {
+                let labels: Vec<String> = value
+                    .split(',')
+                    .map(|label| label.trim().to_string())
+                    .filter(|label| !label.is_empty() && label != "-")
+                    .collect();
+                self.board().edit_labels(&labels);
+            }
+            PromptFields::Priority => {
+                let value = value.trim();
+                let mut chars = value.chars();
+                let priority = match (chars.next(), chars.next()) {
+                    (None, _) => None,
+                    (Some(c), None) if c.is_ascii_alphabetic() => Some(c.to_ascii_uppercase()),
+                    _ => return,
+                };
+                self.board().set_priority(priority);
+            }
+        }
+        self.popup = Popup::None;
+    }
+
+    fn copy(&mut self, cut: bool) {
+        let rows = if cut {
+            self.board().cut_rows()
+        } else {
+            self.board().copy_rows()
+        };
+        if rows.is_empty() {
             return;
         }
-        self.popup = Popup::DeleteRow(DialogState::new("Delete Item?"));
+        if self.system_clipboard {
+            _ = clipboard::copy_to_system(&markdown::cards(&rows, None));
+        }
+        self.clipboard = rows;
     }
 
     pub fn create_item(&mut self, title: &str, description: &str) {
-        self.board
+        self.board()
             .insert_row(title.to_string(), description.to_string());
         self.popup = Popup::None;
     }
 
     pub fn update_item(&mut self, title: &str, description: &str) {
-        self.board
+        self.board()
             .update_row(title.to_string(), description.to_string());
         self.popup = Popup::None;
     }
 
-    pub fn create_column(&mut self, title: &str) {
-        self.board.create_column(title.to_string());
+    pub fn create_column(&mut self, title: &str, index: Option<usize>) {
+        self.board().create_column(title.to_string(), index);
         self.popup = Popup::None;
     }
     pub fn update_column(&mut self, title: &str) {
-        self.board.update_column(title.to_string());
+        self.board().update_column(title.to_string());
         self.popup = Popup::None;
     }
     pub fn delete_column(&mut self) {
-        self.board.delete_column();
-        self.popup = if self.board.columns.is_empty() {
+        self.board().delete_column();
+        self.popup = if self.board().columns.is_empty() {
             Popup::CreateColumn(ColumnPopupState::new(""))
         } else {
             Popup::None
@@ -83,29 +186,175 @@ impl<'a> Model<'a> {
     }
 
     pub fn delete_item(&mut self) {
-        self.board.delete_row();
+        self.board().delete_rows();
         self.popup = Popup::None;
     }
 
+    /// Switches to the tab showing the board stored in `filename`, opening
+    /// it in a new tab if needed. Returns whether it could be opened.
+    fn open_board(&mut self, filename: &str) -> bool {
+        if let Some(tab) = self.boards.iter().position(|b| b.filename() == filename) {
+            self.select_tab(tab);
+            return true;
+        }
+        let Ok(board) = Board::load(filename) else { return false };
+        self.boards.push(board);
+        self.select_tab(self.boards.len() - 1);
+        true
+    }
+
+    /// Shows the board in `tab`, asking for a first column if it has none
+    fn select_tab(&mut self, tab: usize) {
+        self.tab = tab;
+        if self.board().columns.is_empty() {
+            self.popup = Popup::CreateColumn(ColumnPopupState::new(""));
+        }
+    }
+
+    fn switch_tab(&mut self, next: bool) {
+        let count = self.boards.len();
+        self.select_tab(if next {
+            (self.tab + 1) % count
+        } else {
+            (self.tab + count - 1) % count
+        });
+    }
+
+    /// Closes the current tab, unless it's the last one
+    fn close_tab(&mut self) {
+        if self.boards.len() == 1 {
+            return;
+        }
+        self.boards.remove(self.tab);
+        self.select_tab(self.tab.min(self.boards.len() - 1));
+    }
+
+    /// Moves the marked cards, or the selected one, to a column of the board
+    /// in `tab`, switching to it
+    fn move_to(&mut self, tab: usize, column: usize, top: bool) {
+        self.popup = Popup::None;
+        if tab == self.tab {
+            self.board().move_rows_to(column, top);
+            return;
+        }
+        let rows = self.board().cut_rows();
+        self.boards[tab].insert_rows(column, top, rows);
+        self.tab = tab;
+    }
+
+    /// Writes the current board as Markdown next to its file
+    fn export_markdown(&mut self) {
+        let board = self.board();
+        let path = Path::new(board.filename()).with_extension("md");
+        let result = markdown::export(board, &Selection::default(), &MarkdownOptions::default())
+            .and_then(|text| Ok(fs::write(&path, text)?));
+        self.message = Some(match result {
+            Ok(()) => format!("Exported to {}", path.display()),
+            Err(err) => format!("Export failed: {}", err),
+        });
+    }
+
+    /// Selects a card, first opening its board if it isn't the current one
+    fn open_card(&mut self, filename: &str, id: u64) {
+        if self.open_board(filename) {
+            self.board().select_card(id);
+        }
+        self.popup = Popup::None;
+    }
+
+    pub fn perform(&mut self, action: Action) {
+        match action {
+            Action::Up => self.board().up(false),
+            Action::Down => self.board().down(false),
+            Action::Left => self.board().left(false),
+            Action::Right => self.board().right(false),
+            Action::MoveUp => self.board().up(true),
+            Action::MoveDown => self.board().down(true),
+            Action::MoveLeft => self.board().left(true),
+            Action::MoveRight => self.board().right(true),
+            Action::MoveTo => {
+                if !self.board().targets().is_empty() {
+                    self.popup = Popup::MoveTo(MoveToState::new(&self.boards, self.tab));
+                }
+            }
+            Action::SelectColumn(index) => {
+                if index < self.board().columns.len() {
+                    self.board().select_column(index);
+                }
+            }
+            Action::PageUp => self.board().page(false),
+            Action::PageDown => self.board().page(true),
+            Action::First => self.board().first(),
+            Action::Last => self.board().last(),
+            Action::CreateRow => self.popup = Popup::CreateRow(RowPopupState::default()),
+            Action::EditRow => self.edit_item(),
+            Action::DeleteRow => self.open_delete_dialog(),
+            Action::ToggleMark => self.board().toggle_mark(),
+            Action::MarkRange => self.board().mark_range(),
+            Action::ClearMarks => self.board().clear_marks(),
+            Action::MoveMarked => self.board().move_marked(),
+            Action::ArchiveRows => self.board().archive_rows(),
+            Action::ShowArchive => self.popup = Popup::Archive(ArchiveState::new(self.board())),
+            Action::EditLabels => self.open_prompt(PromptFields::Labels),
+            Action::SetPriority => self.open_prompt(PromptFields::Priority),
+            Action::Copy => self.copy(false),
+            Action::Cut => self.copy(true),
+            Action::Paste => self.boards[self.tab].paste_rows(&self.clipboard),
+            Action::Undo => {
+                if !self.board().undo() {
+                    self.message = Some("Nothing to undo".to_string());
+                }
+            }
+            Action::CreateColumn => self.popup = Popup::CreateColumn(ColumnPopupState::new("")),
+            Action::InsertColumnBefore => {
+                let index = self.board().selected_column;
+                self.popup = Popup::CreateColumn(ColumnPopupState::insert(index))
+            }
+            Action::InsertColumnAfter => {
+                let index = self.board().selected_column + 1;
+                self.popup = Popup::CreateColumn(ColumnPopupState::insert(index))
+            }
+            Action::EditColumn => self.edit_column(),
+            Action::DeleteColumn => {
+                self.popup = Popup::DeleteColumn(DialogState::new("Delete Column?"))
+            }
+            Action::MoveColumnLeft => self.board().move_column(false),
+            Action::MoveColumnRight => self.board().move_column(true),
+            Action::DuplicateColumn => self.board().duplicate_column(true),
+            Action::DuplicateEmptyColumn => self.board().duplicate_column(false),
+            Action::WidenColumn => self.board().resize_column(true),
+            Action::NarrowColumn => self.board().resize_column(false),
+            Action::ResetColumnWidth => self.board().reset_column_width(),
+            Action::ToggleCollapsed => self.board().toggle_collapsed(),
+            Action::CycleDensity => self.board().cycle_density(),
+            Action::FindCard => {
+                self.popup = Popup::Finder(FinderState::new(BOARD_DIR, self.board()))
+            }
+            Action::SwitchBoard => {
+                self.popup = Popup::FilePicker(FilePickerState::new(BOARD_DIR))
+            }
+            Action::NextTab => self.switch_tab(true),
+            Action::PreviousTab => self.switch_tab(false),
+            Action::CloseTab => self.close_tab(),
+            Action::ExportMarkdown => self.export_markdown(),
+            Action::CommandPalette => self.popup = Popup::Palette(PaletteState::new(&self.keymap)),
+            Action::Help => self.popup = Popup::Help(0),
+            Action::Quit => self.quit = true,
+        }
+    }
+
     pub fn on_keypress(&mut self, key: KeyEvent) {
+        self.message = None;
         if key.code == KeyCode::Char('c') && key.modifiers == KeyModifiers::CONTROL {
             self.quit = true;
             return;
         }
         match &mut self.popup {
-            Popup::None => match key.code {
-                KeyCode::Char('q') => self.quit = true,
-                KeyCode::Char('c') => self.popup = Popup::CreateRow(RowPopupState::default()),
-                KeyCode::Char('C') => self.popup = Popup::CreateColumn(ColumnPopupState::new("")),
-                KeyCode::Char('E') => self.edit_column(),
-                KeyCode::Enter | KeyCode::Char('e') => self.edit_item(),
-                KeyCode::Backspace | KeyCode::Char('d') => self.open_delete_dialog(),
-                KeyCode::Char('D') => {
-                    self.popup = Popup::DeleteColumn(DialogState::new("Delete Column?"))
-                }
-                KeyCode::Esc => self.popup = Popup::Help,
-                _ => self.board.on_keypress(&key),
-            },
+            Popup::None => {
+                if let Some(action) = self.keymap.action(&key) {
+                    self.perform(action);
+                }
+            }
             Popup::CreateRow(state) => match key {
                 KeyEvent {
                     code: KeyCode::Char('d'),
@@ -148,15 +397,19 @@ impl<'a> Model<'a> {
             },
             Popup::CreateColumn(state) => match key.code {
                 KeyCode::Esc => {
-                    if self.board.columns.is_empty() {
-                        self.quit = true;
-                    } else {
+                    if !self.board().columns.is_empty() {
                         self.popup = Popup::None;
+                    } else if self.boards.len() > 1 {
+                        self.popup = Popup::None;
+                        self.close_tab();
+                    } else {
+                        self.quit = true;
                     }
                 }
                 KeyCode::Enter => {
                     let title = &state.title.lines().join("");
-                    self.create_column(title);
+                    let index = state.index;
+                    self.create_column(title, index);
                 }
                 _ => state.on_keypress(key),
             },
@@ -179,10 +432,162 @@ impl<'a> Model<'a> {
                 }
                 _ => state.on_keypress(key),
             },
-            Popup::Help => match key.code {
+            Popup::Finder(state) => match key.code {
+                KeyCode::Esc => self.popup = Popup::None,
+                KeyCode::Enter => {
+                    let Some(entry) = state.selected() else { return };
+                    let (filename, id) = (entry.filename.clone(), entry.id);
+                    self.open_card(&filename, id);
+                }
+                _ => state.on_keypress(key),
+            },
+            Popup::Palette(state) => match key.code {
+                KeyCode::Esc => self.popup = Popup::None,
+                KeyCode::Enter => {
+                    let Some(action) = state.selected() else { return };
+                    self.popup = Popup::None;
+                    self.perform(action);
+                }
+                _ => state.on_keypress(key),
+            },
+            Popup::FilePicker(state) => match key.code {
+                KeyCode::Esc => self.popup = Popup::None,
+                KeyCode::Enter => {
+                    let Some(file) = state.selected() else { return };
+                    let filename = format!("{}/{}", BOARD_DIR, file);
+                    self.popup = Popup::None;
+                    self.open_board(&filename);
+                }
+                _ => state.on_keypress(key),
+            },
+            Popup::Prompt(state) => match key.code {
+                KeyCode::Esc => self.popup = Popup::None,
+                KeyCode::Enter => {
+                    let (field, value) = (state.field, state.value());
+                    self.submit_prompt(field, &value);
+                }
+                _ => state.on_keypress(key),
+            },
+            Popup::MoveTo(state) => match key.code {
+                KeyCode::Esc => self.popup = Popup::None,
+                KeyCode::Enter => {
+                    let Some((tab, column)) = state.selected() else { return };
+                    let top = state.top;
+                    self.move_to(tab, column, top);
+                }
+                _ => {
+                    if let Some((tab, column)) = state.numbered(key) {
+                        let top = state.top;
+                        self.move_to(tab, column, top);
+                    } else {
+                        state.on_keypress(key);
+                    }
+                }
+            },
+            Popup::Archive(state) => match key.code {
+                KeyCode::Esc => self.popup = Popup::None,
+                KeyCode::Enter => {
+                    let Some(id) = state.selected() else { return };
+                    self.popup = Popup::None;
+                    self.board().unarchive(id);
+                }
+                _ => state.on_keypress(key),
+            },
+            Popup::Help(scroll) => match key.code {
                 KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') => self.popup = Popup::None,
+                KeyCode::Down | KeyCode::Char('j') => *scroll = scroll.saturating_add(1),
+                KeyCode::Up | KeyCode::Char('k') => *scroll = scroll.saturating_sub(1),
+                KeyCode::PageDown => *scroll = scroll.saturating_add(10),
+                KeyCode::PageUp => *scroll = scroll.saturating_sub(10),
                 _ => {}
             },
         }
     }
+
+    pub fn on_mouse(&mut self, mouse: MouseEvent) {
+        let (x, y) = (mouse.column, mouse.row);
+        match &mut self.popup {
+            Popup::None => match mouse.kind {
+                MouseEventKind::Down(MouseButton::Left)
+                    if mouse.modifiers.contains(KeyModifiers::CONTROL) =>
+                {
+                    self.toggle_mark_at(x, y)
+                }
+                MouseEventKind::Down(MouseButton::Left) => self.click(x, y),
+                MouseEventKind::Up(MouseButton::Left) => self.drop(x, y),
+                MouseEventKind::ScrollDown => self.scroll(x, y, true),
+                MouseEventKind::ScrollUp => self.scroll(x, y, false),
+                _ => {}
+            },
+            Popup::DeleteRow(state) | Popup::DeleteColumn(state) => {
+                if mouse.kind != MouseEventKind::Down(MouseButton::Left) {
+                    return;
+                }
+                let Some(button) = state.button_at(x, y) else { return };
+                state.focussed = button;
+                self.on_keypress(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+            }
+            _ => {}
+        }
+    }
+
+    fn click(&mut self, x: u16, y: u16) {
+        if let Some(tab) = self.tab_areas.iter().position(|&area| contains(area, x, y)) {
+            self.select_tab(tab);
+            return;
+        }
+        let Some((column, row)) = self.board().card_at(x, y) else { return };
+        self.board().select_row(column, row);
+        let Some(row) = row else { return };
+
+        let now = Instant::now();
+        let double_click = matches!(
+            self.last_click,
+            Some((time, card)) if card == (column, row) && now - time < DOUBLE_CLICK
+        );
+        if double_click {
+            self.last_click = None;
+            self.edit_item();
+        } else {
+            self.last_click = Some((now, (column, row)));
+            self.drag = Some((column, row));
+        }
+    }
+
+    fn toggle_mark_at(&mut self, x: u16, y: u16) {
+        let Some((column, row @ Some(_))) = self.board().card_at(x, y) else { return };
+        self.board().select_row(column, row);
+        self.board().toggle_mark();
+    }
+
+    /// Drops the card being dragged onto the card under the cursor, or at
+    /// the bottom of the column if there's no card there
+    fn drop(&mut self, x: u16, y: u16) {
+        let Some(origin) = self.drag.take() else { return };
+        let Some((column, row)) = self.board().card_at(x, y) else { return };
+        let destination = match row {
+            Some(row) => (column, row),
+            None if column == origin.0 => (column, self.board().columns[column].rows.len() - 1),
+            None => (column, self.board().columns[column].rows.len()),
+        };
+        if destination != origin {
+            self.last_click = None;
+            self.board().move_row(origin, destination);
+        }
+    }
+
+    /// Moves the selection through the column under the cursor
+    fn scroll(&mut self, x: u16, y: u16, down: bool) {
+        let Some((column, _)) = self.board().card_at(x, y) else { return };
+        let col = &self.board().columns[column];
+        if col.rows.is_empty() {
+            return;
+        }
+        let row = match (col.state.selected(), down) {
+            (Some(row), true) => (row + 1).min(col.rows.len() - 1),
+            (Some(row), false) => row.saturating_sub(1),
+            (None, _) => 0,
+        };
+        self.board().select_row(column, Some(row));
+    }
 }
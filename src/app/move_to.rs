@@ -0,0 +1,93 @@
+use crossterm::event::{KeyCode, KeyEvent};
+
+use super::{
+    board::Board,
+    fuzzy,
+    fuzzy_list::{FuzzyEntry, FuzzyList, SearchField},
+};
+
+/// A column cards can be moved to
+pub struct MoveToEntry {
+    pub tab: usize,
+    pub column: usize,
+    /// Columns of the current board can be picked by their number
+    pub number: Option<usize>,
+    /// The column's title, after its board's for other boards
+    pub title: String,
+}
+
+impl FuzzyEntry for MoveToEntry {
+    fn score(&self, query: &str) -> Option<i64> {
+        fuzzy::score(query, &self.title)
+    }
+}
+
+/// Picks the column to move cards to, on any open board, and whether they go
+/// to its top or bottom
+pub struct MoveToState<'a> {
+    pub list: FuzzyList<'a, MoveToEntry>,
+    pub top: bool,
+}
+
+impl<'a> MoveToState<'a> {
+    /// Lists the columns of the board in `tab` first, then those of the
+    /// other boards
+    pub fn new(boards: &[Board], tab: usize) -> Self {
+        let tabs = std::iter::once(tab).chain((0..boards.len()).filter(|&i| i != tab));
+        let entries = tabs
+            .flat_map(|i| {
+                boards[i]
+                    .columns
+                    .iter()
+                    .enumerate()
+                    .map(move |(column, col)| MoveToEntry {
+                        tab: i,
+                        column,
+                        number: (i == tab).then_some(column + 1),
+                        title: if i == tab {
+                            col.title.clone()
+                        } else {
+                            format!("{} › {}", boards[i].title(), col.title)
+                        },
+                    })
+            })
+            .collect();
+        let field = SearchField {
+            title: "Column",
+            placeholder: "Type a column name or press its number...",
+        };
+        Self {
+            list: FuzzyList::new(field, entries),
+            top: false,
+        }
+    }
+
+    /// The selected column, as (tab, column)
+    pub fn selected(&self) -> Option<(usize, usize)> {
+        let entry = self.list.selected()?;
+        Some((entry.tab, entry.column))
+    }
+
+    /// The column of the current board picked by its number, which only
+    /// works before a name is typed
+    pub fn numbered(&self, key: KeyEvent) -> Option<(usize, usize)> {
+        let KeyCode::Char(c) = key.code else { return None };
+        if !self.list.query.is_empty() {
+            return None;
+        }
+        let number = c.to_digit(10)? as usize;
+        self.list
+            .entries()
+            .iter()
+            .find(|entry| entry.number == Some(number))
+            .map(|entry| (entry.tab, entry.column))
+    }
+
+    pub fn on_keypress(&mut self, key: KeyEvent) {
+        if key.code == KeyCode::Tab {
+            self.top = !self.top;
+        } else {
+            self.list.on_keypress(key);
+        }
+    }
+}
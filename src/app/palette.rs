@@ -0,0 +1,84 @@
+use crossterm::event::KeyEvent;
+
+use super::{
+    fuzzy,
+    fuzzy_list::{FuzzyEntry, FuzzyList, SearchField},
+    Action, Keymap,
+};
+
+pub struct PaletteEntry {
+    pub action: Action,
+    /// The keys currently bound to the action
+    pub keys: String,
+}
+
+impl FuzzyEntry for PaletteEntry {
+    fn score(&self, query: &str) -> Option<i64> {
+        fuzzy::score(query, self.action.title())
+    }
+}
+
+pub struct PaletteState<'a> {
+    pub list: FuzzyList<'a, PaletteEntry>,
+}
+
+impl<'a> PaletteState<'a> {
+    pub fn new(keymap: &Keymap) -> Self {
+        let entries = Action::ALL
+            .iter()
+            .filter(|&&action| action != Action::CommandPalette)
+            .map(|&action| PaletteEntry {
+                action,
+                keys: keymap.keys(action),
+            })
+            .collect();
+        let field = SearchField {
+            title: "Command",
+            placeholder: "Type a command...",
+        };
+        Self {
+            list: FuzzyList::new(field, entries),
+        }
+    }
+
+    pub fn selected(&self) -> Option<Action> {
+        self.list.selected().map(|entry| entry.action)
+    }
+
+    pub fn on_keypress(&mut self, key: KeyEvent) {
+        self.list.on_keypress(key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    /// The palette's best match for `query`
+    fn first_match(query: &str) -> Option<Action> {
+        let mut palette = PaletteState::new(&Keymap::default());
+        for c in query.chars() {
+            palette.on_keypress(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+        palette.selected()
+    }
+
+    #[test]
+    fn ranks_word_starts_first() {
+        assert_eq!(first_match("undo"), Some(Action::Undo));
+        assert_eq!(first_match("dup col"), Some(Action::DuplicateColumn));
+        assert_eq!(first_match("exp"), Some(Action::ExportMarkdown));
+        assert_eq!(first_match("zzz"), None);
+    }
+
+    #[test]
+    fn leaves_out_the_palette_itself() {
+        let palette = PaletteState::new(&Keymap::default());
+        assert!(palette
+            .list
+            .entries()
+            .iter()
+            .all(|entry| entry.action != Action::CommandPalette));
+    }
+}
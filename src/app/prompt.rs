@@ -0,0 +1,50 @@
+use crossterm::event::KeyEvent;
+use tui_textarea::TextArea;
+
+use super::PopupFields;
+
+/// What the text entered in a prompt is used for
+#[derive(PartialEq, Clone, Copy)]
+pub enum PromptFields {
+    Labels,
+    Priority,
+}
+
+impl PopupFields for PromptFields {
+    fn title(&self) -> &str {
+        match self {
+            Self::Labels => "Labels",
+            Self::Priority => "Priority",
+        }
+    }
+
+    fn placeholder(&self) -> &str {
+        match self {
+            Self::Labels => "label, other label, -removed label...",
+            Self::Priority => "A (highest) to Z, empty to clear...",
+        }
+    }
+}
+
+/// A one-line text field asking for a value to apply to the selected cards
+pub struct PromptState<'a> {
+    pub field: PromptFields,
+    pub input: TextArea<'a>,
+}
+
+impl<'a> PromptState<'a> {
+    pub fn new(field: PromptFields) -> Self {
+        Self {
+            field,
+            input: TextArea::default(),
+        }
+    }
+
+    pub fn value(&self) -> String {
+        self.input.lines().join("")
+    }
+
+    pub fn on_keypress(&mut self, key: KeyEvent) {
+        self.input.input(key);
+    }
+}
@@ -0,0 +1,308 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use super::Storage;
+use crate::app::board::{Board, Column, Density, Row};
+
+/// The file holding a directory board's columns and the order of its cards
+pub const MANIFEST: &str = "board.toml";
+/// The directory holding a file for each card, named by its key
+const CARDS_DIR: &str = "cards";
+
+/// Stores a board as a directory, so that changes to different cards never
+/// conflict when merging. The manifest lists the columns and the keys of
+/// their cards in order, and each card is a Markdown file with its details
+/// in YAML front matter above its description.
+///
+/// Cards are named by random keys rather than their IDs, and the manifest
+/// keeps no count of the IDs handed out, so cards added on two branches
+/// only ever meet in the manifest's lists. Where both branches changed the
+/// same list git reports a conflict, and keeping the lines of both sides is
+/// enough to resolve it. Cards left out of the manifest are read into the
+/// first column, and cards that got the same ID on both branches are told
+/// apart by giving the newer one the next free ID.
+pub struct Directory;
+
+#[derive(Serialize, Deserialize)]
+struct Manifest {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    title: Option<String>,
+    #[serde(default)]
+    density: Density,
+    #[serde(default)]
+    columns: Vec<ManifestColumn>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ManifestColumn {
+    title: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    width: Option<u16>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    collapsed: bool,
+    /// Keys of the cards in the column, top first
+    #[serde(default)]
+    cards: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    archived: Vec<String>,
+}
+
+/// The details of a card above its description
+#[derive(Serialize, Deserialize)]
+struct FrontMatter {
+    #[serde(default)]
+    id: u64,
+    title: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    labels: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    priority: Option<char>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    due: Option<String>,
+}
+
+impl Storage for Directory {
+    fn load(&self, path: &str) -> color_eyre::Result<Board> {
+        let dir = Path::new(path);
+        let manifest: Manifest = toml::from_str(&fs::read_to_string(dir.join(MANIFEST))?)?;
+        let mut cards = BTreeMap::new();
+        for (key, file) in card_files(dir) {
+            let row = read_card(&key, &fs::read_to_string(file)?)?;
+            cards.insert(key, row);
+        }
+
+        // Keys start with the time, so of two cards with the same ID the
+        // older one keeps it
+        let mut board = Board::new(manifest.title, path);
+        board.density = manifest.density;
+        board.next_id = cards.values().map(|row| row.id).max().unwrap_or(0);
+        let mut ids = HashSet::new();
+        for row in cards.values_mut() {
+            if row.id == 0 || !ids.insert(row.id) {
+                board.next_id += 1;
+                row.id = board.next_id;
+            }
+        }
+        let mut take = |keys: &[String]| -> Vec<Row> {
+            keys.iter().filter_map(|key| cards.remove(key)).collect()
+        };
+        board.columns = manifest
+            .columns
+            .into_iter()
+            .map(|col| {
+                let mut column = Column::new(col.title);
+                column.width = col.width;
+                column.collapsed = col.collapsed;
+                column.rows = take(&col.cards);
+                column.archived = take(&col.archived);
+                column
+            })
+            .collect();
+        // Cards missing from the manifest, such as those added on another
+        // branch, go to the first column rather than being lost
+        if let Some(first) = board.columns.first_mut() {
+            first.rows.extend(cards.into_values());
+        }
+        Ok(board)
+    }
+
+    fn save(&self, board: &Board, path: &str) -> color_eyre::Result<()> {
+        let dir = Path::new(path);
+        let cards_dir = dir.join(CARDS_DIR);
+        fs::create_dir_all(&cards_dir)?;
+
+        let manifest = Manifest {
+            title: board.title.clone(),
+            density: board.density,
+            columns: board
+                .columns
+                .iter()
+                .map(|col| ManifestColumn {
+                    title: col.title.clone(),
+                    width: col.width,
+                    collapsed: col.collapsed,
+                    cards: col.rows.iter().map(|row| row.key.clone()).collect(),
+                    archived: col.archived.iter().map(|row| row.key.clone()).collect(),
+                })
+                .collect(),
+        };
+        write_if_changed(&dir.join(MANIFEST), &toml::to_string_pretty(&manifest)?)?;
+
+        let mut keys = HashSet::new();
+        for row in board
+            .columns
+            .iter()
+            .flat_map(|col| col.rows.iter().chain(&col.archived))
+        {
+            keys.insert(row.key.as_str());
+            write_if_changed(&card_path(dir, &row.key), &write_card(row)?)?;
+        }
+        for (key, file) in card_files(dir) {
+            if !keys.contains(key.as_str()) {
+                fs::remove_file(file)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn modified(&self, path: &str) -> Option<SystemTime> {
+        let dir = Path::new(path);
+        std::iter::once(dir.join(MANIFEST))
+            .chain(card_files(dir).into_iter().map(|(_, file)| file))
+            .filter_map(|file| fs::metadata(file).and_then(|meta| meta.modified()).ok())
+            .max()
+    }
+
+    fn remove(&self, path: &str) -> color_eyre::Result<()> {
+        let dir = Path::new(path);
+        for (_, file) in card_files(dir) {
+            fs::remove_file(file)?;
+        }
+        fs::remove_file(dir.join(MANIFEST))?;
+        // The directories are kept if anything else is in them
+        _ = fs::remove_dir(dir.join(CARDS_DIR));
+        _ = fs::remove_dir(dir);
+        Ok(())
+    }
+}
+
+fn card_path(dir: &Path, key: &str) -> PathBuf {
+    dir.join(CARDS_DIR).join(format!("{}.md", key))
+}
+
+/// The card files of a board, by key
+fn card_files(dir: &Path) -> Vec<(String, PathBuf)> {
+    let Ok(entries) = fs::read_dir(dir.join(CARDS_DIR)) else { return Vec::new() };
+    entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "md"))
+        .filter_map(|path| {
+            let key = path.file_stem()?.to_str()?.to_string();
+            Some((key, path))
+        })
+        .collect()
+}
+
+/// Writes a file unless it already holds `text`, so that saving leaves the
+/// cards that didn't change untouched
+fn write_if_changed(path: &Path, text: &str) -> color_eyre::Result<()> {
+    if fs::read_to_string(path).ok().as_deref() != Some(text) {
+        fs::write(path, text)?;
+    }
+    Ok(())
+}
+
+fn write_card(row: &Row) -> color_eyre::Result<String> {
+    let front_matter = serde_yaml::to_string(&FrontMatter {
+        id: row.id,
+        title: row.title.clone(),
+        labels: row.labels.clone(),
+        priority: row.priority,
+        due: row.due.clone(),
+    })?;
+    let mut text = format!("---\n{}---\n", front_matter);
+    if !row.description.is_empty() {
+        text.push_str(&format!("\n{}\n", row.description));
+    }
+    Ok(text)
+}
+
+fn read_card(key: &str, text: &str) -> color_eyre::Result<Row> {
+    // Cards edited on Windows may have CRLF line endings
+    let text = text.replace("\r\n", "\n");
+    let (front_matter, description) = text
+        .strip_prefix("---\n")
+        .and_then(|rest| {
+            rest.split_once("\n---\n")
+                .or_else(|| rest.split_once("\n---"))
+        })
+        .ok_or_else(|| {
+            color_eyre::Report::msg(format!("Card {} is missing its front matter", key))
+        })?;
+    let front_matter: FrontMatter = serde_yaml::from_str(front_matter)?;
+    let mut row = Row::new(front_matter.title);
+    row.id = front_matter.id;
+    row.key = key.to_string();
+    row.labels = front_matter.labels;
+    row.priority = front_matter.priority;
+    row.due = front_matter.due;
+    let description = description.strip_prefix('\n').unwrap_or(description);
+    row.description = description
+        .strip_suffix('\n')
+        .unwrap_or(description)
+        .to_string();
+    Ok(row)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_cards_with_crlf_line_endings() {
+        let text =
+            "---\r\nid: 3\r\ntitle: Card\r\nlabels:\r\n- bug\r\n---\r\n\r\nFirst\r\nSecond\r\n";
+        let row = read_card("card", text).unwrap();
+        assert_eq!(row.id, 3);
+        assert_eq!(row.key, "card");
+        assert_eq!(row.title, "Card");
+        assert_eq!(row.labels, vec!["bug".to_string()]);
+        assert_eq!(row.description, "First\nSecond");
+    }
+
+    #[test]
+    fn reads_back_written_cards() {
+        let mut row = Row::new("Card".to_string());
+        row.id = 3;
+        row.description = "First\nSecond".to_string();
+        let read = read_card(&row.key, &write_card(&row).unwrap()).unwrap();
+        assert_eq!(read.id, row.id);
+        assert_eq!(read.title, row.title);
+        assert_eq!(read.description, row.description);
+    }
+
+    #[test]
+    fn merges_cards_added_on_two_branches() {
+        let dir = std::env::temp_dir().join(format!("kb-merge-{}", std::process::id()));
+        let path = dir.to_str().unwrap();
+        let mut board = Board::new(None, path);
+        board.insert_row("Base".to_string(), String::new());
+        let base = board.columns[0].rows[0].key.clone();
+
+        // Both branches add card 2, one of them also listing it in the
+        // manifest
+        let mut ours = Row::new("Ours".to_string());
+        ours.id = 2;
+        ours.key = "00000000001-00000000".to_string();
+        let mut theirs = Row::new("Theirs".to_string());
+        theirs.id = 2;
+        theirs.key = "00000000002-00000000".to_string();
+        board.columns[1].rows.push(theirs.clone());
+        Directory.save(&board, path).unwrap();
+        fs::write(card_path(&dir, &ours.key), write_card(&ours).unwrap()).unwrap();
+
+        let loaded = Directory.load(path).unwrap();
+        let cards: Vec<(&str, u64, &str)> = loaded
+            .columns
+            .iter()
+            .flat_map(|col| &col.rows)
+            .map(|row| (row.title.as_str(), row.id, row.key.as_str()))
+            .collect();
+        assert_eq!(
+            cards,
+            [
+                ("Base", 1, base.as_str()),
+                ("Ours", 2, ours.key.as_str()),
+                ("Theirs", 3, theirs.key.as_str()),
+            ]
+        );
+        assert_eq!(loaded.columns[1].rows[0].title, "Theirs");
+        Directory.remove(path).unwrap();
+    }
+}
@@ -0,0 +1,75 @@
+use std::{fs, path::Path, time::SystemTime};
+
+use super::Storage;
+use crate::app::board::Board;
+
+/// How a board stored in a single file is written, going by the file's
+/// extension
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FileFormat {
+    Json,
+    Yaml,
+    Toml,
+}
+
+impl FileFormat {
+    /// Extensions of board files, in the order they're looked for
+    pub const EXTENSIONS: [&'static str; 4] = ["json", "yaml", "yml", "toml"];
+
+    /// The format of the board file at `path`, or `None` if it doesn't have
+    /// a board file extension
+    pub fn from_path(path: &str) -> Option<Self> {
+        match Path::new(path).extension().and_then(|ext| ext.to_str())? {
+            "json" => Some(Self::Json),
+            "yaml" | "yml" => Some(Self::Yaml),
+            "toml" => Some(Self::Toml),
+            _ => None,
+        }
+    }
+
+    /// Writes a board, pretty-printed so that changes to it diff cleanly
+    pub fn write(self, board: &Board) -> color_eyre::Result<String> {
+        let mut text = match self {
+            Self::Json => serde_json::to_string_pretty(board)?,
+            Self::Yaml => serde_yaml::to_string(board)?,
+            Self::Toml => toml::to_string_pretty(board)?,
+        };
+        if !text.ends_with('\n') {
+            text.push('\n');
+        }
+        Ok(text)
+    }
+
+    pub fn read(self, text: &str) -> color_eyre::Result<Board> {
+        Ok(match self {
+            Self::Json => serde_json::from_str(text)?,
+            Self::Yaml => serde_yaml::from_str(text)?,
+            Self::Toml => toml::from_str(text)?,
+        })
+    }
+}
+
+impl Storage for FileFormat {
+    fn load(&self, path: &str) -> color_eyre::Result<Board> {
+        self.read(&fs::read_to_string(path)?)
+    }
+
+    fn save(&self, board: &Board, path: &str) -> color_eyre::Result<()> {
+        fs::write(path, self.write(board)?)?;
+        Ok(())
+    }
+
+    fn modified(&self, path: &str) -> Option<SystemTime> {
+        fs::metadata(path).and_then(|meta| meta.modified()).ok()
+    }
+
+    fn remove(&self, path: &str) -> color_eyre::Result<()> {
+        fs::remove_file(path)?;
+        Ok(())
+    }
+}
+
+/// Whether a file holds a board, going by its extension
+pub fn is_board_file(path: &str) -> bool {
+    FileFormat::from_path(path).is_some()
+}
@@ -0,0 +1,67 @@
+//! Where boards are kept: in a single JSON, YAML or TOML file, or in a
+//! directory with a file for each card
+
+mod directory;
+mod file;
+
+use std::{path::Path, time::SystemTime};
+
+pub use directory::{Directory, MANIFEST};
+pub use file::{is_board_file, FileFormat};
+
+use super::board::{Board, BOARD_DIR};
+
+/// The board opened when no board is named, stored as `kb.json` or in any
+/// other way under the name `kb`
+const DEFAULT_NAME: &str = "kb";
+
+/// A way of storing boards, each at a path
+pub trait Storage {
+    fn load(&self, path: &str) -> color_eyre::Result<Board>;
+
+    fn save(&self, board: &Board, path: &str) -> color_eyre::Result<()>;
+
+    /// When the board was last changed
+    fn modified(&self, path: &str) -> Option<SystemTime>;
+
+    /// Deletes the board, leaving anything else at its path alone
+    fn remove(&self, path: &str) -> color_eyre::Result<()>;
+}
+
+/// The storage for the board at `path`, a directory unless the path has a
+/// board file extension
+pub fn storage(path: &str) -> Box<dyn Storage> {
+    match FileFormat::from_path(path) {
+        Some(format) => Box::new(format),
+        None => Box::new(Directory),
+    }
+}
+
+/// Whether there's a board at `path`, as a file or a directory
+pub fn is_board(path: &str) -> bool {
+    is_board_file(path) || Path::new(path).join(MANIFEST).is_file()
+}
+
+/// The path of the board called `name` in the board directory. A name with a
+/// board file extension is used as it is, otherwise the first existing file
+/// with one is, then a directory. New boards are JSON files.
+pub fn board_path(name: &str) -> String {
+    find_board(&format!("{}/{}", BOARD_DIR, name))
+}
+
+/// The path of the board opened when no board is named
+pub fn default_board_path() -> String {
+    find_board(DEFAULT_NAME)
+}
+
+fn find_board(base: &str) -> String {
+    if is_board_file(base) {
+        return base.to_string();
+    }
+    FileFormat::EXTENSIONS
+        .iter()
+        .map(|ext| format!("{}.{}", base, ext))
+        .chain(std::iter::once(base.to_string()))
+        .find(|path| Path::new(path).exists() && is_board(path))
+        .unwrap_or_else(|| format!("{}.json", base))
+}
@@ -0,0 +1,441 @@
+//! Subcommands that work on boards without opening the TUI
+
+use color_eyre::Report;
+use std::{fs, path::Path};
+
+use crate::{
+    app::{
+        args::{
+            ConvertArgs, ExportArgs, ExportFormat, ImportArgs, ImportFormat, ListArgs,
+            OutputFormat, QueryArgs, SearchArgs, ShowArgs, StorageFormat, SyncArgs,
+        },
+        board::{Board, Column, BOARD_DIR},
+        storage::{board_path, default_board_path, storage, FileFormat},
+    },
+    formats::{
+        csv,
+        html::{self, HtmlOptions},
+        ics::{self, IcsOptions},
+        json::{self, Card, ColumnCards, SCHEMA_VERSION},
+        markdown::{self, MarkdownOptions},
+        todotxt::{self, TodoTxtOptions},
+        trello, Import, Selection,
+    },
+    get_boolean_input,
+};
+
+/// Reads the board called `name` from the board directory, or the default
+/// board if no name is given
+pub fn read_board(name: Option<&str>) -> color_eyre::Result<Board> {
+    let filename = match name {
+        Some(name) => board_path(name),
+        None => default_board_path(),
+    };
+    Board::load(&filename)
+        .map_err(|err| Report::msg(format!("Failed to read {}: {}", filename, err)))
+}
+
+/// Writes `text` to a file, or to standard output if no file is given
+fn write_output(text: &str, output: Option<&Path>) -> color_eyre::Result<()> {
+    match output {
+        Some(path) => fs::write(path, text)
+            .map_err(|err| Report::msg(format!("Failed to write {}: {}", path.display(), err))),
+        None => {
+            print!("{}", text);
+            Ok(())
+        }
+    }
+}
+
+pub fn export(args: &ExportArgs) -> color_eyre::Result<()> {
+    let board = read_board(args.filename.as_deref())?;
+    let selection = Selection {
+        columns: args.columns.clone(),
+        exclude: args.exclude.clone(),
+    };
+    let text = match args.format {
+        ExportFormat::Markdown => {
+            markdown::export(&board, &selection, &MarkdownOptions { tasks: args.tasks })?
+        }
+        ExportFormat::Csv => csv::export(&board, &selection)?,
+        ExportFormat::Html => html::export(&board, &selection, &HtmlOptions { dark: args.dark })?,
+        ExportFormat::Ics => ics::export(
+            &board,
+            &selection,
+            &IcsOptions {
+                events: args.events,
+            },
+        )?,
+        ExportFormat::Todotxt => todotxt::export(
+            &board,
+            &selection,
+            &TodoTxtOptions {
+                done_column: args.done_column.clone(),
+                ..TodoTxtOptions::default()
+            },
+        )?,
+    };
+    write_output(&text, args.output.as_deref())
+}
+
+/// Prints what an import would add to `board`, column by column
+fn print_preview(import: &Import, board: &Board) {
+    for col in &import.columns {
+        let new = if board.column_index(&col.title).is_some() {
+            ""
+        } else {
+            " (new column)"
+        };
+        let archived = if col.archived.is_empty() {
+            String::new()
+        } else {
+            format!(", {} archived", col.archived.len())
+        };
+        println!(
+            "  {}{}: {} card(s){}",
+            col.title,
+            new,
+            col.rows.len(),
+            archived
+        );
+        for row in &col.rows {
+            let updated = if row.id != 0 && board.has_id(row.id) {
+                " (updated)"
+            } else {
+                ""
+            };
+            println!("    - {}{}", row.title, updated);
+        }
+    }
+    for warning in &import.warnings {
+        println!("Warning: {}", warning);
+    }
+}
+
+pub fn import(args: &ImportArgs) -> color_eyre::Result<()> {
+    let text = fs::read_to_string(&args.file)
+        .map_err(|err| Report::msg(format!("Failed to read {}: {}", args.file.display(), err)))?;
+
+    let mut board = match &args.into {
+        Some(name) => read_board(Some(name))?,
+        None => {
+            let name = match &args.name {
+                Some(name) => name.clone(),
+                None => args
+                    .file
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().to_string())
+                    .ok_or_else(|| Report::msg("Pass --name to name the new board"))?,
+            };
+            let filename = board_path(&name);
+            if Path::new(&filename).exists() {
+                return Err(Report::msg(format!(
+                    "{} already exists, pass --into {} to add the cards to it",
+                    filename, name
+                )));
+            }
+            let mut board = Board::new(None, &filename);
+            board.columns.clear();
+            board
+        }
+    };
+
+    let import = match args.from {
+        ImportFormat::Markdown => markdown::parse(&text),
+        ImportFormat::Trello => trello::parse(&text)?,
+        ImportFormat::Csv => csv::parse(&text)?,
+        ImportFormat::Todotxt => todotxt::parse(
+            &text,
+            &board,
+            &TodoTxtOptions {
+                done_column: args.done_column.clone(),
+                ..TodoTxtOptions::default()
+            },
+        ),
+    };
+    if import.columns.is_empty() {
+        for warning in &import.warnings {
+            println!("Warning: {}", warning);
+        }
+        return Err(Report::msg(format!(
+            "Found no cards or columns in {}",
+            args.file.display()
+        )));
+    }
+    if args.into.is_none() {
+        board.title = import.title.clone();
+    }
+
+    let action = if args.into.is_some() {
+        "Adding"
+    } else {
+        "Creating"
+    };
+    println!(
+        "{} {} card(s) from {} in {}:",
+        action,
+        import.card_count(),
+        args.file.display(),
+        board.filename()
+    );
+    print_preview(&import, &board);
+    if !args.yes && !get_boolean_input("Import? Y/n") {
+        return Ok(());
+    }
+    if args.into.is_none() {
+        fs::create_dir_all(BOARD_DIR)?;
+    }
+    board.import(import.columns)
+}
+
+/// Adds new tasks to the board and new cards to the file. Cards on both
+/// sides are updated from whichever of the two files was changed last.
+/// Nothing is deleted, cards deleted or archived on the board need their
+/// tasks removed by hand.
+pub fn sync(args: &SyncArgs) -> color_eyre::Result<()> {
+    let mut board = read_board(args.board.as_deref())?;
+    let modified = |path: &Path| fs::metadata(path).and_then(|meta| meta.modified()).ok();
+    let text = match fs::read_to_string(&args.file) {
+        Ok(text) => text,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(err) => {
+            return Err(Report::msg(format!(
+                "Failed to read {}: {}",
+                args.file.display(),
+                err
+            )))
+        }
+    };
+    let options = TodoTxtOptions {
+        done_column: args.done_column.clone(),
+        keep_board: board.modified() > modified(&args.file),
+    };
+
+    let import = todotxt::parse(&text, &board, &options);
+    let (mut added, mut updated) = (0, 0);
+    for row in import.columns.iter().flat_map(|col| &col.rows) {
+        if row.id != 0 && board.has_id(row.id) {
+            updated += 1;
+        } else {
+            added += 1;
+        }
+    }
+    for warning in &import.warnings {
+        println!("Warning: {}", warning);
+    }
+    board.import(import.columns)?;
+
+    let tasks = todotxt::export(&board, &Selection::default(), &options)?;
+    write_output(&tasks, Some(&args.file))?;
+    println!(
+        "Added {} card(s) and updated {} in {}, wrote {} task(s) to {}",
+        added,
+        updated,
+        board.filename(),
+        tasks.lines().count(),
+        args.file.display()
+    );
+    Ok(())
+}
+
+/// A card on one line, as `#12 (A) Title #label due 2024-01-31`
+fn summary(card: &Card) -> String {
+    let mut line = format!("#{} ", card.id);
+    if let Some(priority) = card.priority {
+        line.push_str(&format!("({}) ", priority));
+    }
+    line.push_str(&card.title);
+    for label in &card.labels {
+        line.push_str(&format!(" #{}", label));
+    }
+    if let Some(due) = &card.due {
+        line.push_str(&format!(" due {}", due));
+    }
+    if card.archived {
+        line.push_str(" [archived]");
+    }
+    line
+}
+
+/// The cards of a column, archived ones last if asked for
+fn column_cards(col: &Column, archived: bool) -> Vec<Card> {
+    let rows = col
+        .rows
+        .iter()
+        .enumerate()
+        .map(|(i, row)| Card::new(row, col, i, false));
+    let archived_rows = col
+        .archived
+        .iter()
+        .enumerate()
+        .filter(|_| archived)
+        .map(|(i, row)| Card::new(row, col, i, true));
+    rows.chain(archived_rows).collect()
+}
+
+pub fn list(args: &ListArgs) -> color_eyre::Result<()> {
+    let board = read_board(args.query.board.as_deref())?;
+    let selection = Selection {
+        columns: args.columns.clone(),
+        ..Selection::default()
+    };
+    let columns: Vec<ColumnCards> = selection
+        .apply(&board)?
+        .into_iter()
+        .map(|col| ColumnCards {
+            title: col.title.clone(),
+            done: col.is_done(),
+            cards: column_cards(col, args.archived),
+        })
+        .collect();
+    let text = match args.query.format {
+        OutputFormat::Json => json::write(&json::List {
+            schema: SCHEMA_VERSION,
+            board: (&board).into(),
+            columns,
+        })?,
+        OutputFormat::Text => {
+            let mut text = String::new();
+            for col in columns {
+                text.push_str(&format!("{} ({})\n", col.title, col.cards.len()));
+                for card in &col.cards {
+                    text.push_str(&format!("  {}\n", summary(card)));
+                }
+            }
+            text
+        }
+    };
+    write_output(&text, None)
+}
+
+pub fn show(args: &ShowArgs) -> color_eyre::Result<()> {
+    let board = read_board(args.query.board.as_deref())?;
+    let card = board
+        .columns
+        .iter()
+        .flat_map(|col| column_cards(col, true))
+        .find(|card| card.id == args.id)
+        .ok_or_else(|| {
+            Report::msg(format!(
+                "No card with ID {} in {}",
+                args.id,
+                board.filename()
+            ))
+        })?;
+    let text = match args.query.format {
+        OutputFormat::Json => json::write(&json::Show {
+            schema: SCHEMA_VERSION,
+            board: (&board).into(),
+            card,
+        })?,
+        OutputFormat::Text => {
+            let mut text = format!("{}\nColumn: {}\n", summary(&card), card.column);
+            if !card.description.is_empty() {
+                text.push_str(&format!("\n{}\n", card.description));
+            }
+            text
+        }
+    };
+    write_output(&text, None)
+}
+
+pub fn search(args: &SearchArgs) -> color_eyre::Result<()> {
+    let board = read_board(args.query.board.as_deref())?;
+    let query = args.text.to_lowercase();
+    let matches = |card: &Card| {
+        card.title.to_lowercase().contains(&query)
+            || card.description.to_lowercase().contains(&query)
+            || card
+                .labels
+                .iter()
+                .any(|label| label.to_lowercase().contains(&query))
+    };
+    let cards: Vec<Card> = board
+        .columns
+        .iter()
+        .flat_map(|col| column_cards(col, args.archived))
+        .filter(matches)
+        .collect();
+    let text = match args.query.format {
+        OutputFormat::Json => json::write(&json::Search {
+            schema: SCHEMA_VERSION,
+            board: (&board).into(),
+            query: args.text.clone(),
+            cards,
+        })?,
+        OutputFormat::Text if cards.is_empty() => format!("No cards match `{}`\n", args.text),
+        OutputFormat::Text => cards
+            .iter()
+            .map(|card| format!("{} ({})\n", summary(card), card.column))
+            .collect(),
+    };
+    write_output(&text, None)
+}
+
+pub fn stats(args: &QueryArgs) -> color_eyre::Result<()> {
+    let board = read_board(args.board.as_deref())?;
+    let stats = json::Stats::new(&board);
+    let text = match args.format {
+        OutputFormat::Json => json::write(&stats)?,
+        OutputFormat::Text => {
+            let mut text = format!(
+                "{} card(s), {} done, {} archived, {} with a due date\n",
+                stats.cards, stats.done, stats.archived, stats.with_due_date
+            );
+            for col in &stats.columns {
+                text.push_str(&format!("  {}: {}\n", col.title, col.cards));
+            }
+            if !stats.labels.is_empty() {
+                let labels: Vec<String> = stats
+                    .labels
+                    .iter()
+                    .map(|(label, count)| format!("#{} {}", label, count))
+                    .collect();
+                text.push_str(&format!("Labels: {}\n", labels.join(", ")));
+            }
+            if !stats.priorities.is_empty() {
+                let priorities: Vec<String> = stats
+                    .priorities
+                    .iter()
+                    .map(|(priority, count)| format!("({}) {}", priority, count))
+                    .collect();
+                text.push_str(&format!("Priorities: {}\n", priorities.join(", ")));
+            }
+            text
+        }
+    };
+    write_output(&text, None)
+}
+
+/// Stores a board in another way, removing the old file or directory unless
+/// asked to keep it
+pub fn convert(args: &ConvertArgs) -> color_eyre::Result<()> {
+    let mut board = read_board(args.filename.as_deref())?;
+    let source = board.filename().to_string();
+    let base = match source.rsplit_once('.') {
+        Some((base, ext)) if FileFormat::EXTENSIONS.contains(&ext) => base,
+        _ => source.as_str(),
+    };
+    let target = match args.to {
+        StorageFormat::Json => format!("{}.json", base),
+        StorageFormat::Yaml => format!("{}.yaml", base),
+        StorageFormat::Toml => format!("{}.toml", base),
+        StorageFormat::Directory => base.to_string(),
+    };
+    if target == source {
+        return Err(Report::msg(format!(
+            "{} is already stored that way",
+            source
+        )));
+    }
+    if Path::new(&target).exists() {
+        return Err(Report::msg(format!("{} already exists", target)));
+    }
+
+    board.save_as(&target)?;
+    if !args.keep {
+        storage(&source).remove(&source)?;
+    }
+    println!("Converted {} to {}", source, target);
+    Ok(())
+}
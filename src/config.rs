@@ -0,0 +1,184 @@
+use color_eyre::Report;
+use serde::Deserialize;
+use std::{
+    collections::BTreeMap,
+    env, fs,
+    path::{Path, PathBuf},
+};
+use tui::style::Style;
+
+use crate::{
+    app::Keymap,
+    ui::{parse_color, parse_modifier, Theme},
+};
+
+/// One key or a list of keys bound to an action
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Keys {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl Keys {
+    fn into_vec(self) -> Vec<String> {
+        match self {
+            Keys::One(key) => vec![key],
+            Keys::Many(keys) => keys,
+        }
+    }
+}
+
+/// The style of a theme element, either just a foreground color or a table
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StyleFile {
+    Color(String),
+    Style {
+        fg: Option<String>,
+        bg: Option<String>,
+        #[serde(default)]
+        modifiers: Vec<String>,
+    },
+}
+
+impl StyleFile {
+    fn to_style(&self) -> Result<Style, String> {
+        match self {
+            StyleFile::Color(fg) => Ok(Style::default().fg(parse_color(fg)?)),
+            StyleFile::Style { fg, bg, modifiers } => {
+                let mut style = Style::default();
+                if let Some(fg) = fg {
+                    style = style.fg(parse_color(fg)?);
+                }
+                if let Some(bg) = bg {
+                    style = style.bg(parse_color(bg)?);
+                }
+                for modifier in modifiers {
+                    style = style.add_modifier(parse_modifier(modifier)?);
+                }
+                Ok(style)
+            }
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct ThemeFile {
+    /// The built-in theme to start from, `dark` if not given
+    base: Option<String>,
+    #[serde(flatten)]
+    styles: BTreeMap<String, StyleFile>,
+}
+
+/// The user's config file, as written on disk
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct ConfigFile {
+    keys: BTreeMap<String, Keys>,
+    theme: Option<String>,
+    themes: BTreeMap<String, ThemeFile>,
+    description_lines: Option<usize>,
+    system_clipboard: bool,
+}
+
+impl ConfigFile {
+    fn theme(&self, errors: &mut Vec<String>) -> Theme {
+        let Some(name) = &self.theme else { return Theme::from_env() };
+        // Custom themes come first, so that one named after a built-in theme
+        // replaces it
+        let Some(theme_file) = self.themes.get(name) else {
+            return Theme::builtin(name).unwrap_or_else(|| {
+                errors.push(format!(
+                    "unknown theme `{}`, expected one of {} or a theme in [themes]",
+                    name,
+                    Theme::BUILTIN.join(", ")
+                ));
+                Theme::from_env()
+            });
+        };
+
+        let base = theme_file.base.as_deref().unwrap_or("dark");
+        let mut theme = Theme::builtin(base).unwrap_or_else(|| {
+            errors.push(format!("themes.{}: unknown base theme `{}`", name, base));
+            Theme::dark()
+        });
+        for (element, style) in &theme_file.styles {
+            if let Err(err) = style.to_style().and_then(|style| theme.set(element, style)) {
+                errors.push(format!("themes.{}.{}: {}", name, element, err));
+            }
+        }
+        theme
+    }
+}
+
+pub struct Config {
+    pub keymap: Keymap,
+    pub theme: Theme,
+    /// Card descriptions on the board are cut short after this many lines
+    pub description_lines: Option<usize>,
+    /// Copied cards also go to the system clipboard
+    pub system_clipboard: bool,
+}
+
+impl Config {
+    /// Where the config file lives when `--config` isn't given:
+    /// `$XDG_CONFIG_HOME/kb/config.toml`, falling back to `~/.config`
+    pub fn default_path() -> Option<PathBuf> {
+        let dir = env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+        Some(dir.join("kb").join("config.toml"))
+    }
+
+    /// Loads the config from `path`, or from the default location if none is
+    /// given. A missing default config file is not an error.
+    pub fn load(path: Option<&Path>) -> color_eyre::Result<Self> {
+        let file = match path {
+            Some(path) => Some((path.to_path_buf(), fs::read_to_string(path)?)),
+            None => Self::default_path()
+                .and_then(|path| fs::read_to_string(&path).ok().map(|file| (path, file))),
+        };
+        let Some((path, file)) = file else { return Ok(Self::default()) };
+
+        let mut config: ConfigFile = toml::from_str(&file).map_err(|err| {
+            Report::msg(format!("Invalid config file {}: {}", path.display(), err))
+        })?;
+
+        let mut errors = Vec::new();
+        let theme = config.theme(&mut errors);
+        let keys = std::mem::take(&mut config.keys)
+            .into_iter()
+            .map(|(action, keys)| (action, keys.into_vec()))
+            .collect();
+        let keymap = Keymap::with_overrides(&keys).unwrap_or_else(|key_errors| {
+            errors.extend(key_errors);
+            Keymap::default()
+        });
+
+        if !errors.is_empty() {
+            return Err(Report::msg(format!(
+                "Invalid config file {}:\n  {}",
+                path.display(),
+                errors.join("\n  ")
+            )));
+        }
+        Ok(Self {
+            keymap,
+            theme,
+            description_lines: config.description_lines,
+            system_clipboard: config.system_clipboard,
+        })
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            keymap: Keymap::default(),
+            theme: Theme::from_env(),
+            description_lines: None,
+            system_clipboard: false,
+        }
+    }
+}
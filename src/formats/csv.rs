@@ -0,0 +1,166 @@
+//! Reading and writing cards as CSV, one row per card, for spreadsheets
+
+use color_eyre::Report;
+use serde::{Deserialize, Serialize};
+
+use super::{is_date, Import, Selection, DEFAULT_COLUMN};
+use crate::app::board::{Board, Row};
+
+/// A card as a CSV row. Labels are separated by `;`, with a `;` or `\` in a
+/// label escaped by a `\`.
+#[derive(Serialize, Deserialize)]
+struct Record {
+    id: Option<u64>,
+    #[serde(default)]
+    column: String,
+    /// Where the card is in its column, counting from 1
+    position: Option<usize>,
+    title: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    labels: String,
+    priority: Option<String>,
+    due: Option<String>,
+    archived: Option<bool>,
+}
+
+/// Writes the cards of a board as CSV, archived cards after the others in
+/// each column
+pub fn export(board: &Board, selection: &Selection) -> color_eyre::Result<String> {
+    let mut writer = ::csv::Writer::from_writer(Vec::new());
+    for col in selection.apply(board)? {
+        let rows = col.rows.iter().map(|row| (row, false));
+        let archived = col.archived.iter().map(|row| (row, true));
+        for (position, (row, archived)) in rows.enumerate().chain(archived.enumerate()) {
+            writer.serialize(Record {
+                id: Some(row.id),
+                column: col.title.clone(),
+                position: Some(position + 1),
+                title: row.title.clone(),
+                description: row.description.clone(),
+                labels: join_labels(&row.labels),
+                priority: row.priority.map(String::from),
+                due: row.due.clone(),
+                archived: Some(archived),
+            })?;
+        }
+    }
+    let bytes = writer
+        .into_inner()
+        .map_err(|err| Report::msg(err.to_string()))?;
+    Ok(String::from_utf8(bytes)?)
+}
+
+/// Reads cards written by `export`. Only the title is needed, cards without
+/// a column go to a "To Do" column and those without a position go after
+/// the others.
+pub fn parse(text: &str) -> color_eyre::Result<Import> {
+    let mut import = Import::default();
+    let mut reader = ::csv::Reader::from_reader(text.as_bytes());
+    let mut cards = Vec::new();
+    for record in reader.deserialize() {
+        let record: Record =
+            record.map_err(|err| Report::msg(format!("Failed to read the CSV: {}", err)))?;
+        let mut row = Row::new(record.title);
+        row.id = record.id.unwrap_or(0);
+        row.description = record.description;
+        row.labels = split_labels(&record.labels);
+        row.priority = match record.priority.as_deref().map(str::trim) {
+            None | Some("") => None,
+            Some(priority) => match priority.chars().next() {
+                Some(c) if priority.len() == 1 && c.is_ascii_alphabetic() => {
+                    Some(c.to_ascii_uppercase())
+                }
+                _ => {
+                    import.warnings.push(format!(
+                        "Skipped the priority `{}` of `{}`, it should be a letter from A to Z",
+                        priority, row.title
+                    ));
+                    None
+                }
+            },
+        };
+        row.due = match record.due.as_deref().map(str::trim) {
+            None | Some("") => None,
+            Some(due) if is_date(due) => Some(due.to_string()),
+            Some(due) => {
+                import.warnings.push(format!(
+                    "Skipped the due date `{}` of `{}`, it should look like 2024-01-31",
+                    due, row.title
+                ));
+                None
+            }
+        };
+
+        let title = match record.column.trim() {
+            "" => DEFAULT_COLUMN.to_string(),
+            title => title.to_string(),
+        };
+        // Columns are added in the order they first appear
+        import.column(&title);
+        let position = record.position.unwrap_or(usize::MAX);
+        cards.push((title, position, record.archived.unwrap_or(false), row));
+    }
+
+    // The sort is stable, so cards in the same position keep the file's order
+    cards.sort_by_key(|(_, position, _, _)| *position);
+    for (title, _, archived, row) in cards {
+        let column = import.column(&title);
+        if archived {
+            column.archived.push(row);
+        } else {
+            column.rows.push(row);
+        }
+    }
+    Ok(import)
+}
+
+fn join_labels(labels: &[String]) -> String {
+    labels
+        .iter()
+        .map(|label| label.replace('\\', "\\\\").replace(';', "\\;"))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+fn split_labels(text: &str) -> Vec<String> {
+    let mut labels = Vec::new();
+    let mut label = String::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => label.extend(chars.next()),
+            ';' => labels.push(std::mem::take(&mut label)),
+            c => label.push(c),
+        }
+    }
+    labels.push(label);
+    labels
+        .into_iter()
+        .map(|label| label.trim().to_string())
+        .filter(|label| !label.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_labels_with_spaces_and_separators() {
+        let labels = vec![
+            "High Priority".to_string(),
+            "a;b".to_string(),
+            "back\\slash".to_string(),
+        ];
+        assert_eq!(split_labels(&join_labels(&labels)), labels);
+    }
+
+    #[test]
+    fn uppercases_priorities() {
+        let import = parse("title,priority\nCard,b\n").unwrap();
+        assert_eq!(import.columns[0].rows[0].priority, Some('B'));
+        assert!(import.warnings.is_empty());
+    }
+}
@@ -0,0 +1,206 @@
+//! Writing a board as a single HTML page, laid out like the board in the
+//! terminal
+
+use pulldown_cmark::{html, Event, Parser, Tag};
+
+use super::Selection;
+use crate::app::board::{Board, Row};
+
+#[derive(Default)]
+pub struct HtmlOptions {
+    /// Uses light text on a dark background
+    pub dark: bool,
+}
+
+const STYLE: &str = "
+body { margin: 0; padding: 1.5rem; font-family: system-ui, sans-serif; background: #f6f7f9; color: #1f2328; }
+h1 { margin: 0 0 1rem; font-size: 1.5rem; }
+.board { display: flex; gap: 1rem; align-items: flex-start; overflow-x: auto; }
+.column { flex: 0 0 18rem; border: 1px solid #d0d7de; border-radius: 6px; background: #fff; }
+.column h2 { margin: 0; padding: 0.5rem 0.75rem; font-size: 1rem; border-bottom: 1px solid #d0d7de; }
+.column h2 .count { font-weight: normal; color: #656d76; }
+.card { margin: 0.5rem; padding: 0.5rem 0.75rem; border: 1px solid #d0d7de; border-radius: 6px; }
+.card h3 { margin: 0; font-size: 0.95rem; }
+.meta { margin-top: 0.25rem; font-size: 0.8rem; color: #656d76; }
+.label { color: #0969da; margin-right: 0.5rem; }
+.description { margin-top: 0.5rem; font-size: 0.9rem; }
+.description > :first-child { margin-top: 0; }
+.description > :last-child { margin-bottom: 0; }
+.description pre { overflow-x: auto; padding: 0.5rem; background: #f6f8fa; }
+";
+
+const DARK_STYLE: &str = "
+body { background: #0d1117; color: #e6edf3; }
+.column, .card { background: #161b22; border-color: #30363d; }
+.column h2 { border-color: #30363d; }
+.column h2 .count, .meta { color: #8d96a0; }
+.label { color: #4493f8; }
+.description pre { background: #0d1117; }
+";
+
+/// Writes a board as a self-contained HTML page, with the styles inline and
+/// card descriptions rendered as Markdown
+pub fn export(
+    board: &Board,
+    selection: &Selection,
+    options: &HtmlOptions,
+) -> color_eyre::Result<String> {
+    let columns = selection.apply(board)?;
+    let title = escape(&board.title());
+    let mut text = String::from("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+    text.push_str(&format!("<title>{}</title>\n<style>{}", title, STYLE));
+    if options.dark {
+        text.push_str(DARK_STYLE);
+    }
+    text.push_str(&format!(
+        "</style>\n</head>\n<body>\n<h1>{}</h1>\n<div class=\"board\">\n",
+        title
+    ));
+    for col in columns {
+        // Columns given a width on the board keep it, in characters
+        let width = match col.width {
+            Some(width) => format!(" style=\"flex-basis: {}ch\"", width),
+            None => String::new(),
+        };
+        text.push_str(&format!(
+            "<section class=\"column\"{}>\n<h2>{} <span class=\"count\">{}</span></h2>\n",
+            width,
+            escape(&col.title),
+            col.rows.len()
+        ));
+        for row in &col.rows {
+            card(&mut text, row);
+        }
+        text.push_str("</section>\n");
+    }
+    text.push_str("</div>\n</body>\n</html>\n");
+    Ok(text)
+}
+
+fn card(text: &mut String, row: &Row) {
+    text.push_str("<article class=\"card\">\n<h3>");
+    if let Some(priority) = row.priority {
+        text.push_str(&format!("({}) ", priority));
+    }
+    text.push_str(&escape(&row.title));
+    text.push_str("</h3>\n");
+    if row.due.is_some() || !row.labels.is_empty() {
+        text.push_str("<div class=\"meta\">");
+        for label in &row.labels {
+            text.push_str(&format!("<span class=\"label\">#{}</span>", escape(label)));
+        }
+        if let Some(due) = &row.due {
+            text.push_str(&format!("due {}", escape(due)));
+        }
+        text.push_str("</div>\n");
+    }
+    if !row.description.trim().is_empty() {
+        text.push_str("<div class=\"description\">\n");
+        // HTML in descriptions is shown as written rather than included in
+        // the page, and links and images with an unsafe URL are left out,
+        // keeping their text. Images can be nested in links, so whether each
+        // open one was left out is kept on a stack.
+        let mut left_out = Vec::new();
+        let events = Parser::new(&row.description).filter_map(move |event| match event {
+            Event::Html(html) => Some(Event::Text(html)),
+            Event::Start(Tag::Link(_, ref url, _) | Tag::Image(_, ref url, _)) => {
+                let safe = is_safe_url(url);
+                left_out.push(!safe);
+                safe.then_some(event)
+            }
+            Event::End(Tag::Link(..) | Tag::Image(..)) => {
+                (!left_out.pop().unwrap_or(false)).then_some(event)
+            }
+            event => Some(event),
+        });
+        html::push_html(text, events);
+        text.push_str("</div>\n");
+    }
+    text.push_str("</article>\n");
+}
+
+/// Whether a link can go to `url`: web and mail addresses, and URLs relative
+/// to the page. Browsers ignore whitespace and control characters in a
+/// scheme, so they're skipped here too.
+fn is_safe_url(url: &str) -> bool {
+    let url: String = url
+        .chars()
+        .filter(|c| !c.is_ascii_whitespace() && !c.is_ascii_control())
+        .collect();
+    match url.split_once(':') {
+        Some((scheme, _)) if !scheme.contains(['/', '?', '#']) => {
+            ["http", "https", "mailto"].contains(&scheme.to_ascii_lowercase().as_str())
+        }
+        _ => true,
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn description(text: &str) -> String {
+        let mut row = Row::new("Card".to_string());
+        row.description = text.to_string();
+        let mut html = String::new();
+        card(&mut html, &row);
+        html
+    }
+
+    #[test]
+    fn keeps_web_mail_and_relative_links() {
+        for url in [
+            "https://example.com",
+            "http://a.b/c",
+            "mailto:me@a.b",
+            "notes/a.md",
+            "#top",
+        ] {
+            let html = description(&format!("[link]({}) ![image]({})", url, url));
+            assert!(
+                html.contains(&format!("<a href=\"{}\">link</a>", url)),
+                "{}",
+                html
+            );
+            assert!(html.contains(&format!("<img src=\"{}\"", url)), "{}", html);
+        }
+    }
+
+    #[test]
+    fn turns_other_links_into_text() {
+        for url in [
+            "javascript:alert(1)",
+            "JavaScript:alert(1)",
+            "data:text/html,x",
+            "vbscript:x",
+        ] {
+            let html = description(&format!("[link]({}) ![image]({}) <{}>", url, url, url));
+            assert!(!html.contains("<a "), "{}", html);
+            assert!(!html.contains("<img "), "{}", html);
+            assert!(html.contains("link image"), "{}", html);
+        }
+        let html = description("[link](<java\tscript:alert(1)>)");
+        assert!(!html.contains("<a "), "{}", html);
+
+        let html = description("[![image](a.png)](javascript:alert(1))");
+        assert!(
+            html.contains("<img src=\"a.png\" alt=\"image\" />"),
+            "{}",
+            html
+        );
+        assert!(!html.contains("<a "), "{}", html);
+    }
+
+    #[test]
+    fn shows_html_as_text() {
+        let html = description("<script>alert(1)</script>");
+        assert!(html.contains("&lt;script&gt;"), "{}", html);
+    }
+}
@@ -0,0 +1,212 @@
+//! Writing the due dates of cards as an iCalendar file, for calendar apps
+
+use std::{
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use super::Selection;
+use crate::app::board::{Board, Row};
+
+#[derive(Default)]
+pub struct IcsOptions {
+    /// Writes all-day events instead of to-dos, for calendars that don't
+    /// show to-dos
+    pub events: bool,
+}
+
+/// Writes a to-do, or an event, for each card with a due date. The UIDs come
+/// from the board's file name and the card's ID, so calendars update the
+/// entries they already have when the file is imported again.
+pub fn export(
+    board: &Board,
+    selection: &Selection,
+    options: &IcsOptions,
+) -> color_eyre::Result<String> {
+    let columns = selection.apply(board)?;
+    let name = Path::new(board.filename())
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let stamp = timestamp(SystemTime::now());
+
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        format!("PRODID:-//kb//kb {}//EN", env!("CARGO_PKG_VERSION")),
+        "CALSCALE:GREGORIAN".to_string(),
+        format!("X-WR-CALNAME:{}", escape(&board.title())),
+    ];
+    for col in columns {
+        let done = col.is_done();
+        for row in &col.rows {
+            let Some(due) = row.due.as_deref().and_then(date) else { continue };
+            let uid = format!("UID:kb-{}-{}@kb", name, row.id);
+            if options.events {
+                lines.push("BEGIN:VEVENT".to_string());
+                lines.push(uid);
+                lines.push(format!("DTSTAMP:{}", stamp));
+                lines.push(format!("DTSTART;VALUE=DATE:{}", due));
+                // Events can't be completed, so done cards are ticked off
+                // in their summary instead
+                let check = if done { "✓ " } else { "" };
+                lines.push(format!("SUMMARY:{}{}", check, escape(&row.title)));
+                details(&mut lines, row);
+                lines.push("END:VEVENT".to_string());
+            } else {
+                lines.push("BEGIN:VTODO".to_string());
+                lines.push(uid);
+                lines.push(format!("DTSTAMP:{}", stamp));
+                lines.push(format!("DUE;VALUE=DATE:{}", due));
+                lines.push(format!("SUMMARY:{}", escape(&row.title)));
+                let status = if done { "COMPLETED" } else { "NEEDS-ACTION" };
+                lines.push(format!("STATUS:{}", status));
+                details(&mut lines, row);
+                lines.push("END:VTODO".to_string());
+            }
+        }
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    Ok(lines.iter().map(|line| fold(line)).collect())
+}
+
+/// Adds the description, labels and priority of a card
+fn details(lines: &mut Vec<String>, row: &Row) {
+    if !row.description.is_empty() {
+        lines.push(format!("DESCRIPTION:{}", escape(&row.description)));
+    }
+    if !row.labels.is_empty() {
+        let labels: Vec<String> = row.labels.iter().map(|label| escape(label)).collect();
+        lines.push(format!("CATEGORIES:{}", labels.join(",")));
+    }
+    // Priorities go from 1 (highest) to 9, so those after I are all 9
+    if let Some(priority) = row.priority {
+        let priority = (priority as u32).saturating_sub('A' as u32).min(8) + 1;
+        lines.push(format!("PRIORITY:{}", priority));
+    }
+}
+
+/// A due date written as `YYYY-MM-DD`, as `YYYYMMDD`
+fn date(due: &str) -> Option<String> {
+    let date: String = due.chars().filter(|&c| c != '-').collect();
+    (date.len() == 8 && date.chars().all(|c| c.is_ascii_digit())).then_some(date)
+}
+
+/// A time in UTC, as `YYYYMMDDTHHMMSSZ`
+fn timestamp(time: SystemTime) -> String {
+    let seconds = time.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    let (days, seconds) = (seconds / 86400, seconds % 86400);
+    // Converts days since 1970 to a date in the Gregorian calendar, from
+    // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days as i64 + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}{:02}{:02}T{:02}{:02}{:02}Z",
+        year,
+        month,
+        day,
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+/// Escapes text for use in a property value
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Ends a line with CRLF, splitting it into lines of at most 75 bytes that
+/// continue after a space
+fn fold(line: &str) -> String {
+    let mut folded = String::new();
+    let mut length = 0;
+    for c in line.chars() {
+        if length + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            length = 1;
+        }
+        folded.push(c);
+        length += c.len_utf8();
+    }
+    folded.push_str("\r\n");
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn writes_timestamps_in_utc() {
+        let at = |seconds| timestamp(UNIX_EPOCH + Duration::from_secs(seconds));
+        assert_eq!(at(0), "19700101T000000Z");
+        assert_eq!(at(946684799), "19991231T235959Z");
+        assert_eq!(at(951868800), "20000301T000000Z");
+        assert_eq!(at(1709210096), "20240229T123456Z");
+    }
+
+    #[test]
+    fn folds_long_lines_between_characters() {
+        assert_eq!(fold("SUMMARY:Short"), "SUMMARY:Short\r\n");
+        for line in [
+            format!("SUMMARY:{}", "a".repeat(100)),
+            format!("SUMMARY:{}", "é".repeat(100)),
+            format!("SUMMARY:{}", "✓".repeat(100)),
+        ] {
+            let folded = fold(&line);
+            assert!(
+                folded.split("\r\n").all(|part| part.len() <= 75),
+                "{}",
+                folded
+            );
+            assert_eq!(folded.replace("\r\n ", ""), format!("{}\r\n", line));
+        }
+    }
+
+    #[test]
+    fn writes_a_to_do_for_each_card_due() {
+        let mut board = Board::new(Some("Plans, 2024".to_string()), ".kb/plans.json");
+        let mut row = Row::new("Release".to_string());
+        row.id = 3;
+        row.due = Some("2024-05-01".to_string());
+        row.priority = Some('B');
+        board.columns[2].rows.push(row);
+        board.columns[0]
+            .rows
+            .push(Row::new("No due date".to_string()));
+        let text = export(&board, &Selection::default(), &IcsOptions::default()).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(
+            lines.iter().filter(|line| **line == "BEGIN:VTODO").count(),
+            1
+        );
+        for line in [
+            "X-WR-CALNAME:Plans\\, 2024",
+            "UID:kb-plans-3@kb",
+            "DUE;VALUE=DATE:20240501",
+            "STATUS:COMPLETED",
+            "PRIORITY:2",
+        ] {
+            assert!(lines.contains(&line), "{} in {}", line, text);
+        }
+
+        let options = IcsOptions { events: true };
+        let text = export(&board, &Selection::default(), &options).unwrap();
+        assert!(text.contains("UID:kb-plans-3@kb\r\n"), "{}", text);
+        assert!(text.contains("DTSTART;VALUE=DATE:20240501\r\n"), "{}", text);
+        assert!(text.contains("SUMMARY:✓ Release\r\n"), "{}", text);
+    }
+}
@@ -0,0 +1,282 @@
+//! The JSON written by the read commands for scripts. It's kept apart from
+//! the board file so that the file can change without breaking scripts: any
+//! change to these types that isn't adding a field needs `SCHEMA_VERSION`
+//! bumped and the README updated.
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use crate::app::board::{Board, Column, Row};
+
+pub const SCHEMA_VERSION: u32 = 1;
+
+#[derive(Serialize)]
+pub struct BoardInfo {
+    pub title: String,
+    pub file: String,
+}
+
+impl From<&Board> for BoardInfo {
+    fn from(board: &Board) -> Self {
+        Self {
+            title: board.title(),
+            file: board.filename().to_string(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct Card {
+    pub id: u64,
+    pub title: String,
+    pub description: String,
+    pub labels: Vec<String>,
+    pub priority: Option<char>,
+    /// As `YYYY-MM-DD`
+    pub due: Option<String>,
+    pub column: String,
+    /// Where the card is in its column, counting from 1. Archived cards are
+    /// counted separately.
+    pub position: usize,
+    pub archived: bool,
+    /// Whether the card's column is a done column
+    pub done: bool,
+}
+
+impl Card {
+    pub fn new(row: &Row, col: &Column, index: usize, archived: bool) -> Self {
+        Self {
+            id: row.id,
+            title: row.title.clone(),
+            description: row.description.clone(),
+            labels: row.labels.clone(),
+            priority: row.priority,
+            due: row.due.clone(),
+            column: col.title.clone(),
+            position: index + 1,
+            archived,
+            done: col.is_done(),
+        }
+    }
+}
+
+/// The cards of a column, by `kb list`
+#[derive(Serialize)]
+pub struct ColumnCards {
+    pub title: String,
+    pub done: bool,
+    pub cards: Vec<Card>,
+}
+
+/// Written by `kb list`
+#[derive(Serialize)]
+pub struct List {
+    pub schema: u32,
+    pub board: BoardInfo,
+    pub columns: Vec<ColumnCards>,
+}
+
+/// Written by `kb show`
+#[derive(Serialize)]
+pub struct Show {
+    pub schema: u32,
+    pub board: BoardInfo,
+    pub card: Card,
+}
+
+/// Written by `kb search`
+#[derive(Serialize)]
+pub struct Search {
+    pub schema: u32,
+    pub board: BoardInfo,
+    pub query: String,
+    pub cards: Vec<Card>,
+}
+
+/// The number of cards in a column, by `kb stats`
+#[derive(Serialize)]
+pub struct ColumnStats {
+    pub title: String,
+    pub done: bool,
+    pub cards: usize,
+    pub archived: usize,
+}
+
+/// Written by `kb stats`. Archived cards are only counted in `archived`.
+#[derive(Serialize)]
+pub struct Stats {
+    pub schema: u32,
+    pub board: BoardInfo,
+    pub cards: usize,
+    pub done: usize,
+    pub archived: usize,
+    pub with_due_date: usize,
+    pub columns: Vec<ColumnStats>,
+    /// The number of cards with each label
+    pub labels: BTreeMap<String, usize>,
+    /// The number of cards with each priority
+    pub priorities: BTreeMap<char, usize>,
+}
+
+impl Stats {
+    pub fn new(board: &Board) -> Self {
+        let rows = || board.columns.iter().flat_map(|col| &col.rows);
+        let mut labels = BTreeMap::new();
+        let mut priorities = BTreeMap::new();
+        for row in rows() {
+            for label in &row.labels {
+                *labels.entry(label.clone()).or_default() += 1;
+            }
+            if let Some(priority) = row.priority {
+                *priorities.entry(priority).or_default() += 1;
+            }
+        }
+        Self {
+            schema: SCHEMA_VERSION,
+            board: board.into(),
+            cards: rows().count(),
+            done: board
+                .columns
+                .iter()
+                .filter(|col| col.is_done())
+                .map(|col| col.rows.len())
+                .sum(),
+            archived: board.columns.iter().map(|col| col.archived.len()).sum(),
+            with_due_date: rows().filter(|row| row.due.is_some()).count(),
+            columns: board
+                .columns
+                .iter()
+                .map(|col| ColumnStats {
+                    title: col.title.clone(),
+                    done: col.is_done(),
+                    cards: col.rows.len(),
+                    archived: col.archived.len(),
+                })
+                .collect(),
+            labels,
+            priorities,
+        }
+    }
+}
+
+/// Writes output for scripts, pretty-printed
+pub fn write<T: Serialize>(output: &T) -> color_eyre::Result<String> {
+    let mut text = serde_json::to_string_pretty(output)?;
+    text.push('\n');
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    fn board() -> Board {
+        let mut board = Board::new(Some("Plans".to_string()), ".kb/plans.json");
+        let mut row = Row::new("Release".to_string());
+        row.id = 3;
+        row.description = "Tag it".to_string();
+        row.labels = vec!["ops".to_string()];
+        row.priority = Some('A');
+        row.due = Some("2024-05-01".to_string());
+        board.columns[2].rows.push(row);
+        let mut row = Row::new("Old".to_string());
+        row.id = 1;
+        board.columns[0].archived.push(row);
+        board
+    }
+
+    /// The output as scripts read it
+    fn value<T: Serialize>(output: &T) -> Value {
+        serde_json::from_str(&write(output).unwrap()).unwrap()
+    }
+
+    // Changing what these expect breaks scripts, see the module's docs
+    #[test]
+    fn keeps_the_card_schema() {
+        let board = board();
+        let col = &board.columns[2];
+        let show = Show {
+            schema: SCHEMA_VERSION,
+            board: (&board).into(),
+            card: Card::new(&col.rows[0], col, 0, false),
+        };
+        assert_eq!(
+            value(&show),
+            json!({
+                "schema": 1,
+                "board": {"title": "Plans", "file": ".kb/plans.json"},
+                "card": {
+                    "id": 3,
+                    "title": "Release",
+                    "description": "Tag it",
+                    "labels": ["ops"],
+                    "priority": "A",
+                    "due": "2024-05-01",
+                    "column": "Done",
+                    "position": 1,
+                    "archived": false,
+                    "done": true,
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn keeps_the_list_and_search_schemas() {
+        let board = board();
+        let col = &board.columns[0];
+        let list = List {
+            schema: SCHEMA_VERSION,
+            board: (&board).into(),
+            columns: vec![ColumnCards {
+                title: col.title.clone(),
+                done: false,
+                cards: vec![Card::new(&col.archived[0], col, 0, true)],
+            }],
+        };
+        let list = value(&list);
+        assert_eq!(list["columns"][0]["title"], "To Do");
+        assert_eq!(list["columns"][0]["done"], false);
+        assert_eq!(list["columns"][0]["cards"][0]["archived"], true);
+        assert_eq!(list["columns"][0]["cards"][0]["priority"], Value::Null);
+
+        let search = Search {
+            schema: SCHEMA_VERSION,
+            board: (&board).into(),
+            query: "old".to_string(),
+            cards: Vec::new(),
+        };
+        assert_eq!(
+            value(&search),
+            json!({
+                "schema": 1,
+                "board": {"title": "Plans", "file": ".kb/plans.json"},
+                "query": "old",
+                "cards": [],
+            })
+        );
+    }
+
+    #[test]
+    fn keeps_the_stats_schema() {
+        assert_eq!(
+            value(&Stats::new(&board())),
+            json!({
+                "schema": 1,
+                "board": {"title": "Plans", "file": ".kb/plans.json"},
+                "cards": 1,
+                "done": 1,
+                "archived": 1,
+                "with_due_date": 1,
+                "columns": [
+                    {"title": "To Do", "done": false, "cards": 0, "archived": 1},
+                    {"title": "In Progress", "done": false, "cards": 0, "archived": 0},
+                    {"title": "Done", "done": true, "cards": 1, "archived": 0},
+                ],
+                "labels": {"ops": 1},
+                "priorities": {"A": 1},
+            })
+        );
+    }
+}
@@ -0,0 +1,354 @@
+use super::{Import, Selection, DEFAULT_COLUMN};
+use crate::app::board::{is_done_column, Board, Row};
+
+#[derive(Default)]
+pub struct MarkdownOptions {
+    /// Writes cards as task list items, checked in columns that are done
+    pub tasks: bool,
+}
+
+/// Writes a board as Markdown, with a heading for the board and one for each
+/// column followed by its cards
+pub fn export(
+    board: &Board,
+    selection: &Selection,
+    options: &MarkdownOptions,
+) -> color_eyre::Result<String> {
+    let columns = selection.apply(board)?;
+    let mut text = format!("# {}\n", board.title());
+    for col in columns {
+        text.push_str(&format!("\n## {}\n", col.title));
+        if !col.rows.is_empty() {
+            text.push('\n');
+            text.push_str(&cards(&col.rows, options.tasks.then(|| col.is_done())));
+        }
+    }
+    Ok(text)
+}
+
+/// Writes cards as a Markdown list, one item per card with its description
+/// indented below it. Items are task list items if `checked` is given.
+pub fn cards(rows: &[Row], checked: Option<bool>) -> String {
+    let mut text = String::new();
+    for row in rows {
+        text.push_str(match checked {
+            Some(true) => "- [x] ",
+            Some(false) => "- [ ] ",
+            None => "- ",
+        });
+        if let Some(priority) = row.priority {
+            text.push_str(&format!("({}) ", priority));
+        }
+        text.push_str(&row.title);
+        for label in &row.labels {
+            text.push_str(&format!(" #{}", label));
+        }
+        text.push('\n');
+        for line in row.description.lines() {
+            if !line.is_empty() {
+                text.push_str("  ");
+                text.push_str(line);
+            }
+            text.push('\n');
+        }
+    }
+    text
+}
+
+/// Checked task list items go to this column, unless they're already in a
+/// column that's done
+const DONE_COLUMN: &str = "Done";
+
+/// A list item being read, along with the lines nested under it
+struct Item {
+    row: Row,
+    checked: bool,
+    /// Lines indented this far belong to the item
+    indent: usize,
+    /// Lines nested under the item, with its indentation removed
+    lines: Vec<String>,
+}
+
+impl Item {
+    fn finish(self, import: &mut Import, column: &str) {
+        let mut row = self.row;
+        let start = self.lines.iter().position(|line| !line.trim().is_empty());
+        let end = self.lines.iter().rposition(|line| !line.trim().is_empty());
+        if let (Some(start), Some(end)) = (start, end) {
+            row.description = self.lines[start..=end].join("\n");
+        }
+        let column = if self.checked && !is_done_column(column) {
+            DONE_COLUMN
+        } else {
+            column
+        };
+        import.column(column).rows.push(row);
+    }
+}
+
+/// Reads a Markdown document as a board. Headings become columns and list
+/// items become cards, with anything nested under an item as its
+/// description. A lone top-level heading above the others is the board's
+/// title.
+pub fn parse(text: &str) -> Import {
+    let mut import = Import::default();
+    let headings: Vec<usize> = text
+        .lines()
+        .filter_map(|line| heading(line).map(|(level, _)| level))
+        .collect();
+    let has_title = headings.first() == Some(&1) && !headings[1..].contains(&1);
+
+    let mut column = DEFAULT_COLUMN.to_string();
+    let mut item: Option<Item> = None;
+    let mut in_code_block = false;
+    let mut skipped = 0;
+    for line in text.lines() {
+        let indent = line.len() - unindent(line).len();
+        let fence = line.trim_start().starts_with("```") || line.trim_start().starts_with("~~~");
+        let code = in_code_block || fence;
+        if fence {
+            in_code_block = !in_code_block;
+        }
+
+        if let Some(item) = &mut item {
+            if line.trim().is_empty() || indent >= item.indent || (code && indent > 0) {
+                item.lines.push(line[indent.min(item.indent)..].to_string());
+                continue;
+            }
+        }
+        // Anything not nested under the item being read ends it
+        if let Some(item) = item.take() {
+            item.finish(&mut import, &column);
+        }
+
+        if line.trim().is_empty() {
+            continue;
+        }
+        if code {
+            skipped += 1;
+        } else if let Some((level, title)) = heading(line) {
+            if has_title && level == 1 && import.title.is_none() {
+                import.title = Some(title.to_string());
+            } else {
+                column = title.to_string();
+                import.column(&column);
+            }
+        } else if let Some((marker, content)) = list_item(line) {
+            let (checked, content) = task(content);
+            item = Some(Item {
+                row: card(content),
+                checked,
+                indent: indent + marker + 1,
+                lines: Vec::new(),
+            });
+        } else {
+            skipped += 1;
+        }
+    }
+    if let Some(item) = item.take() {
+        item.finish(&mut import, &column);
+    }
+    // A done column that only holds checked items goes last
+    let done_heading = text
+        .lines()
+        .any(|line| heading(line).is_some_and(|(_, title)| title == DONE_COLUMN));
+    if let Some(index) = import
+        .columns
+        .iter()
+        .position(|col| col.title == DONE_COLUMN)
+    {
+        if !done_heading {
+            let done = import.columns.remove(index);
+            import.columns.push(done);
+        }
+    }
+    if skipped > 0 {
+        import.warnings.push(format!(
+            "Skipped {} line(s) that aren't headings or list items",
+            skipped
+        ));
+    }
+    import
+}
+
+/// Splits a heading into its level and text
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    let title = line[level..].strip_prefix(' ')?;
+    (1..=6)
+        .contains(&level)
+        .then(|| (level, title.trim().trim_end_matches('#').trim()))
+}
+
+/// A line without its indentation. Only spaces and tabs indent, as in
+/// CommonMark, other whitespace is part of the content.
+fn unindent(line: &str) -> &str {
+    line.trim_start_matches([' ', '\t'])
+}
+
+/// Splits a list item into the width of its marker and its content
+fn list_item(line: &str) -> Option<(usize, &str)> {
+    let line = unindent(line);
+    let digits = line.chars().take_while(char::is_ascii_digit).count();
+    let marker = if digits > 0 {
+        match line[digits..].chars().next() {
+            Some('.') | Some(')') => digits + 1,
+            _ => return None,
+        }
+    } else {
+        match line.chars().next() {
+            Some('-') | Some('*') | Some('+') => 1,
+            _ => return None,
+        }
+    };
+    let content = line[marker..].strip_prefix(' ')?;
+    Some((marker, content))
+}
+
+/// Strips a task list checkbox, returning whether it was checked
+fn task(content: &str) -> (bool, &str) {
+    if let Some(rest) = content.strip_prefix("[ ] ") {
+        (false, rest)
+    } else if let Some(rest) = content
+        .strip_prefix("[x] ")
+        .or_else(|| content.strip_prefix("[X] "))
+    {
+        (true, rest)
+    } else {
+        (false, content)
+    }
+}
+
+/// Reads a card's title, along with a leading `(A)` priority and trailing
+/// `#label`s as written by `cards`
+fn card(content: &str) -> Row {
+    let mut title = content.trim();
+    let mut priority = None;
+    let mut chars = title.chars();
+    if let (Some('('), Some(p), Some(')'), Some(' ')) =
+        (chars.next(), chars.next(), chars.next(), chars.next())
+    {
+        if p.is_ascii_uppercase() {
+            priority = Some(p);
+            title = title[4..].trim_start();
+        }
+    }
+
+    let mut labels = Vec::new();
+    while let Some((rest, label)) = title.rsplit_once(" #") {
+        // `#123` is more likely an issue number than a label
+        if label.is_empty() || label.contains(' ') || label.chars().all(|c| c.is_ascii_digit()) {
+            break;
+        }
+        labels.insert(0, label.to_string());
+        title = rest.trim_end();
+    }
+
+    let mut row = Row::new(title.to_string());
+    row.priority = priority;
+    row.labels = labels;
+    row
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_indents_with_spaces_and_tabs() {
+        let import = parse("- Card\n  \u{3000}nested\n- Other\n\u{3000}not nested\n");
+        let rows = &import.columns[0].rows;
+        assert_eq!(rows[0].description, "\u{3000}nested");
+        assert_eq!(rows[1].description, "");
+        assert_eq!(import.warnings.len(), 1);
+    }
+
+    fn board() -> Board {
+        let mut board = Board::new(Some("Plans".to_string()), "plans.json");
+        let mut row = Row::new("Fix login".to_string());
+        row.priority = Some('A');
+        row.labels = vec!["bug".to_string(), "ui".to_string()];
+        row.description = "Steps:\n\n```\nclick\n```\n- not a card".to_string();
+        board.columns[0].rows.push(row);
+        board.columns[0]
+            .rows
+            .push(Row::new("Write docs".to_string()));
+        board.columns[2].rows.push(Row::new("Release".to_string()));
+        board
+    }
+
+    /// Each column's title with the cards read back from it
+    fn columns(import: &Import) -> Vec<(&str, Vec<&Row>)> {
+        import
+            .columns
+            .iter()
+            .map(|col| (col.title.as_str(), col.rows.iter().collect()))
+            .collect()
+    }
+
+    #[test]
+    fn reads_back_exported_boards() {
+        let board = board();
+        let text = export(&board, &Selection::default(), &MarkdownOptions::default()).unwrap();
+        let import = parse(&text);
+        assert_eq!(import.title.as_deref(), Some("Plans"));
+        assert!(import.warnings.is_empty());
+        let columns = columns(&import);
+        let titles: Vec<&str> = columns.iter().map(|(title, _)| *title).collect();
+        assert_eq!(titles, ["To Do", "In Progress", "Done"]);
+        for (col, (_, rows)) in board.columns.iter().zip(&columns) {
+            assert_eq!(col.rows.len(), rows.len());
+            for (row, read) in col.rows.iter().zip(rows) {
+                assert_eq!(read.title, row.title);
+                assert_eq!(read.priority, row.priority);
+                assert_eq!(read.labels, row.labels);
+                assert_eq!(read.description, row.description);
+            }
+        }
+    }
+
+    #[test]
+    fn reads_back_checked_tasks_into_a_done_column() {
+        let mut board = board();
+        board.columns[2].title = "Finished".to_string();
+        let options = MarkdownOptions { tasks: true };
+        let text = export(&board, &Selection::default(), &options).unwrap();
+        assert!(text.contains("## Finished\n\n- [x] Release\n"), "{}", text);
+        let import = parse(&text);
+        let titles: Vec<&str> = import
+            .columns
+            .iter()
+            .map(|col| col.title.as_str())
+            .collect();
+        assert_eq!(titles, ["To Do", "In Progress", "Finished"]);
+
+        // Checked items under other headings go to a new column, last
+        let import = parse("- [x] Release\n- [ ] Write docs\n");
+        let titles: Vec<&str> = import
+            .columns
+            .iter()
+            .map(|col| col.title.as_str())
+            .collect();
+        assert_eq!(titles, ["To Do", "Done"]);
+        assert_eq!(import.columns[1].rows[0].title, "Release");
+    }
+
+    #[test]
+    fn exports_the_selected_columns() {
+        let selection = Selection {
+            exclude: vec!["in progress".to_string()],
+            ..Selection::default()
+        };
+        let text = export(&board(), &selection, &MarkdownOptions::default()).unwrap();
+        assert!(!text.contains("In Progress"), "{}", text);
+        assert!(text.contains("## Done"), "{}", text);
+    }
+
+    #[test]
+    fn reads_nothing_from_an_empty_document() {
+        assert!(parse("").columns.is_empty());
+        let import = parse("# Title only\n");
+        assert!(import.columns.is_empty());
+        assert_eq!(import.title.as_deref(), Some("Title only"));
+    }
+}
@@ -0,0 +1,116 @@
+//! Reading and writing boards in formats other than kb's own
+
+pub mod csv;
+pub mod html;
+pub mod ics;
+pub mod json;
+pub mod markdown;
+pub mod todotxt;
+pub mod trello;
+
+use color_eyre::Report;
+
+use crate::app::board::{Board, Column, Row};
+
+/// Cards that don't say which column they're in go to this one
+const DEFAULT_COLUMN: &str = "To Do";
+
+/// Cards read from another format, to be added to the column with the same
+/// title
+pub struct ImportColumn {
+    pub title: String,
+    pub rows: Vec<Row>,
+    /// Cards that were archived in the other format, kept archived
+    pub archived: Vec<Row>,
+}
+
+/// A board read from another format, before it's added to a kb board
+#[derive(Default)]
+pub struct Import {
+    pub title: Option<String>,
+    pub columns: Vec<ImportColumn>,
+    /// Anything that couldn't be imported, to report to the user
+    pub warnings: Vec<String>,
+}
+
+impl Import {
+    /// The column called `title`, added if there isn't one yet
+    fn column(&mut self, title: &str) -> &mut ImportColumn {
+        let index = match self.columns.iter().position(|col| col.title == title) {
+            Some(index) => index,
+            None => {
+                self.columns.push(ImportColumn {
+                    title: title.to_string(),
+                    rows: Vec::new(),
+                    archived: Vec::new(),
+                });
+                self.columns.len() - 1
+            }
+        };
+        &mut self.columns[index]
+    }
+
+    pub fn card_count(&self) -> usize {
+        self.columns.iter().map(|col| col.rows.len()).sum()
+    }
+}
+
+/// Which columns of a board to export. Columns are named by their titles,
+/// ignoring case.
+#[derive(Default)]
+pub struct Selection {
+    /// Titles of the columns to include, all of them if empty
+    pub columns: Vec<String>,
+    /// Titles of the columns to leave out
+    pub exclude: Vec<String>,
+}
+
+impl Selection {
+    /// The selected columns, in board order
+    pub fn apply<'a>(&self, board: &'a Board) -> color_eyre::Result<Vec<&'a Column>> {
+        let has_column = |name: &String| {
+            board
+                .columns
+                .iter()
+                .any(|col| col.title.eq_ignore_ascii_case(name))
+        };
+        let unknown: Vec<&String> = self
+            .columns
+            .iter()
+            .chain(&self.exclude)
+            .filter(|name| !has_column(name))
+            .collect();
+        if !unknown.is_empty() {
+            let titles: Vec<&str> = board.columns.iter().map(|col| col.title.as_str()).collect();
+            return Err(Report::msg(format!(
+                "Unknown column {}, the board has: {}",
+                unknown
+                    .iter()
+                    .map(|name| format!("`{}`", name))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                titles.join(", ")
+            )));
+        }
+
+        let listed = |names: &[String], col: &Column| {
+            names
+                .iter()
+                .any(|name| col.title.eq_ignore_ascii_case(name))
+        };
+        Ok(board
+            .columns
+            .iter()
+            .filter(|col| self.columns.is_empty() || listed(&self.columns, col))
+            .filter(|col| !listed(&self.exclude, col))
+            .collect())
+    }
+}
+
+/// Whether `text` looks like a date written as `YYYY-MM-DD`
+fn is_date(text: &str) -> bool {
+    let parts: Vec<&str> = text.split('-').collect();
+    matches!(parts.as_slice(), [year, month, day]
+        if year.len() == 4 && month.len() == 2 && day.len() == 2
+            && parts.iter().all(|part| part.chars().all(|c| c.is_ascii_digit())))
+}
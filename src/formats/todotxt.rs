@@ -0,0 +1,424 @@
+//! Reading and writing cards in the todo.txt format, one task per line
+//!
+//! Labels are written as `+project` tokens, or as they are if they start
+//! with `@`, like contexts. Whitespace and `%` in them are percent-encoded,
+//! so `next week` is written as `+next%20week`. Each task ends with a `kb:` tag holding the ID of
+//! its card, which is how the tasks of a file are matched to the cards of a
+//! board. Titles are written as they are, and read back using the card's
+//! title, so that words in them that look like tags stay in the title.
+
+use super::{is_date, Import, Selection, DEFAULT_COLUMN};
+use crate::app::board::{Board, Column, Row};
+
+/// Completed tasks go to this column if the board has no done column
+const DONE_COLUMN: &str = "Done";
+
+#[derive(Default)]
+pub struct TodoTxtOptions {
+    /// The column completed tasks go to, instead of the board's first done
+    /// column
+    pub done_column: Option<String>,
+    /// Leaves cards the board already has as they are, only adding new ones
+    pub keep_board: bool,
+}
+
+/// A line of a todo.txt file
+struct Task {
+    row: Row,
+    done: bool,
+}
+
+/// Writes the cards of a board as tasks, completed for those in done
+/// columns. Descriptions aren't written, todo.txt has no room for them.
+pub fn export(
+    board: &Board,
+    selection: &Selection,
+    options: &TodoTxtOptions,
+) -> color_eyre::Result<String> {
+    let mut text = String::new();
+    for col in selection.apply(board)? {
+        let done = is_done(col, options);
+        for row in &col.rows {
+            text.push_str(&line(row, done));
+            text.push('\n');
+        }
+    }
+    Ok(text)
+}
+
+/// Whether the cards in a column are written as completed tasks
+fn is_done(col: &Column, options: &TodoTxtOptions) -> bool {
+    col.is_done() || options.done_column.as_ref() == Some(&col.title)
+}
+
+fn line(row: &Row, done: bool) -> String {
+    let mut words = Vec::new();
+    if done {
+        words.push("x".to_string());
+    } else if let Some(priority) = row.priority {
+        words.push(format!("({})", priority));
+    }
+    words.push(row.title.clone());
+    for label in &row.labels {
+        if label.starts_with('@') {
+            words.push(encode(label));
+        } else {
+            words.push(format!("+{}", encode(label)));
+        }
+    }
+    if let Some(due) = &row.due {
+        words.push(format!("due:{}", due));
+    }
+    // Completed tasks lose their priority, so it's kept in a tag instead
+    if let (true, Some(priority)) = (done, row.priority) {
+        words.push(format!("pri:{}", priority));
+    }
+    words.push(format!("kb:{}", row.id));
+    words.join(" ")
+}
+
+/// Reads tasks into the columns of `board`. Tasks with the ID of a card on
+/// the board update it and stay in its column, unless they were completed or
+/// reopened. Other tasks go to the board's first column if open, and to its
+/// done column if completed.
+pub fn parse(text: &str, board: &Board, options: &TodoTxtOptions) -> Import {
+    let mut import = Import::default();
+    let open_column = board
+        .columns
+        .iter()
+        .find(|col| !col.is_done())
+        .map_or(DEFAULT_COLUMN, |col| col.title.as_str());
+    let done_column = match &options.done_column {
+        Some(title) => title.as_str(),
+        None => board
+            .columns
+            .iter()
+            .find(|col| col.is_done())
+            .map_or(DONE_COLUMN, |col| col.title.as_str()),
+    };
+
+    let mut archived = 0;
+    for line in text.lines() {
+        let id = id(line);
+        let existing = board.columns.iter().find_map(|col| {
+            let row = col.rows.iter().find(|row| row.id != 0 && row.id == id)?;
+            Some((col, row))
+        });
+        let Some(task) = task(line, existing.map(|(_, row)| row.title.as_str())) else {
+            continue;
+        };
+        let column = match existing {
+            Some((col, row)) => {
+                let done = is_done(col, options);
+                if options.keep_board || (done == task.done && unchanged(row, &task.row)) {
+                    continue;
+                }
+                if done == task.done {
+                    col.title.as_str()
+                } else if task.done {
+                    done_column
+                } else {
+                    open_column
+                }
+            }
+            None if task.row.id != 0 && board.has_id(task.row.id) => {
+                archived += 1;
+                continue;
+            }
+            None if task.done => done_column,
+            None => open_column,
+        };
+        let mut row = task.row;
+        if let Some((_, card)) = existing {
+            row.description = card.description.clone();
+        }
+        import.column(column).rows.push(row);
+    }
+
+    // Completed tasks go last when their column is new
+    if let Some(index) = import
+        .columns
+        .iter()
+        .position(|col| col.title == done_column)
+    {
+        let done = import.columns.remove(index);
+        import.columns.push(done);
+    }
+    if archived > 0 {
+        import.warnings.push(format!(
+            "Skipped {} task(s) whose cards are archived",
+            archived
+        ));
+    }
+    import
+}
+
+/// Whether a task holds the same details as the card it came from
+fn unchanged(card: &Row, task: &Row) -> bool {
+    card.title == task.title
+        && card.labels == task.labels
+        && card.priority == task.priority
+        && card.due == task.due
+}
+
+/// The ID in a line's `kb:` tag, 0 if it has none. The last tag is the one
+/// written by `export`, any others are part of the title.
+fn id(line: &str) -> u64 {
+    line.split_whitespace()
+        .rev()
+        .find_map(|word| word.strip_prefix("kb:")?.parse().ok())
+        .unwrap_or(0)
+}
+
+/// Reads a line such as `x 2024-01-02 (A) Title +project @context due:2024-01-31`.
+/// `title` is the title of the card the line was written from, if there is
+/// one: where the line holds it as it is, its words are read as the title
+/// even if they look like tags, a priority or a completion mark.
+fn task(line: &str, title: Option<&str>) -> Option<Task> {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    let (mut start, mut done, mut priority) = prefix(&words);
+    let mut title_words = Vec::new();
+    let known: Vec<&str> = title.unwrap_or_default().split_whitespace().collect();
+    if !known.is_empty() {
+        // The title goes after the completion mark, priority and dates
+        let position = (0..words.len())
+            .find(|&i| words[i..].starts_with(&known) && prefix(&words[..i]).0 == i);
+        if let Some(i) = position {
+            (_, done, priority) = prefix(&words[..i]);
+            title_words = known.clone();
+            start = i + known.len();
+        }
+    }
+
+    let mut row = Row::new(String::new());
+    row.priority = priority;
+    for &word in &words[start..] {
+        if let Some(label) = word.strip_prefix('+').filter(|label| !label.is_empty()) {
+            row.labels.push(decode(label));
+        } else if word.len() > 1 && word.starts_with('@') {
+            row.labels.push(decode(word));
+        } else if let Some(due) = word.strip_prefix("due:").filter(|due| is_date(due)) {
+            row.due = Some(due.to_string());
+        } else if let Some(p) = word.strip_prefix("pri:").and_then(letter) {
+            row.priority = Some(p);
+        } else if let Some(id) = word.strip_prefix("kb:").and_then(|id| id.parse().ok()) {
+            row.id = id;
+        } else {
+            title_words.push(word);
+        }
+    }
+    if title_words.is_empty() {
+        return None;
+    }
+    row.title = title_words.join(" ");
+    Some(Task { row, done })
+}
+
+/// Percent-encodes the whitespace and `%` in a label, so it stays one word
+fn encode(label: &str) -> String {
+    let mut text = String::new();
+    for c in label.chars() {
+        if c.is_whitespace() || c == '%' {
+            for byte in c.to_string().bytes() {
+                text.push_str(&format!("%{:02X}", byte));
+            }
+        } else {
+            text.push(c);
+        }
+    }
+    text
+}
+
+/// Reads a label written by `encode`. A `%` that isn't followed by two hex
+/// digits is kept as it is.
+fn decode(word: &str) -> String {
+    let mut bytes = Vec::new();
+    let mut rest = word.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        let digits = match tail {
+            [high, low, ..] if byte == b'%' => {
+                let digit = |byte: &u8| char::from(*byte).to_digit(16);
+                digit(high).zip(digit(low))
+            }
+            _ => None,
+        };
+        match digits {
+            Some((high, low)) => {
+                bytes.push((high * 16 + low) as u8);
+                rest = &tail[2..];
+            }
+            None => {
+                bytes.push(byte);
+                rest = tail;
+            }
+        }
+    }
+    String::from_utf8_lossy(&bytes).to_string()
+}
+
+/// Reads the completion mark, priority and dates at the start of a task,
+/// returning how many words they take up
+fn prefix(words: &[&str]) -> (usize, bool, Option<char>) {
+    let done = words.first() == Some(&"x");
+    let mut count = usize::from(done);
+    let priority = words.get(count).and_then(|word| priority(word));
+    count += usize::from(priority.is_some());
+    // The completion and creation dates aren't kept
+    while words.get(count).is_some_and(|word| is_date(word)) {
+        count += 1;
+    }
+    (count, done, priority)
+}
+
+/// A priority written as `(A)`
+fn priority(word: &str) -> Option<char> {
+    letter(word.strip_prefix('(')?.strip_suffix(')')?)
+}
+
+/// A priority from `A` to `Z`
+fn letter(text: &str) -> Option<char> {
+    let mut chars = text.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if c.is_ascii_uppercase() => Some(c),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A board with a card whose title looks like a priority, a completion
+    /// mark and tags
+    fn board() -> Board {
+        let mut board = Board::new(None, "test.json");
+        let mut row = Row::new("(B) x +ui @home due:2024-01-01 kb:9".to_string());
+        row.id = 5;
+        row.labels = vec!["real".to_string()];
+        row.priority = Some('A');
+        board.columns[0].rows.push(row);
+        board
+    }
+
+    #[test]
+    fn reads_back_titles_that_look_like_tags() {
+        let board = board();
+        let options = TodoTxtOptions::default();
+        let text = export(&board, &Selection::default(), &options).unwrap();
+        assert!(parse(&text, &board, &options).columns.is_empty());
+
+        let done = line(&board.columns[0].rows[0], true);
+        let task = task(&done, Some(&board.columns[0].rows[0].title)).unwrap();
+        assert!(task.done);
+        assert!(unchanged(&board.columns[0].rows[0], &task.row));
+    }
+
+    /// The titles of the cards read into each column
+    fn columns(import: &Import) -> Vec<(&str, Vec<&str>)> {
+        import
+            .columns
+            .iter()
+            .map(|col| {
+                let titles = col.rows.iter().map(|row| row.title.as_str()).collect();
+                (col.title.as_str(), titles)
+            })
+            .collect()
+    }
+
+    /// A board with an open card 1, a done card 2 and an archived card 3
+    fn merge_board() -> Board {
+        let mut board = Board::new(None, "test.json");
+        for (column, id, title) in [(0, 1, "Open"), (2, 2, "Shipped")] {
+            let mut row = Row::new(title.to_string());
+            row.id = id;
+            row.description = format!("About {}", title);
+            board.columns[column].rows.push(row);
+        }
+        let mut row = Row::new("Old".to_string());
+        row.id = 3;
+        board.columns[0].archived.push(row);
+        board
+    }
+
+    #[test]
+    fn updates_cards_in_their_column() {
+        let board = merge_board();
+        let options = TodoTxtOptions::default();
+        let text = "(A) Open +now kb:1\nx Shipped kb:2\n";
+        let import = parse(text, &board, &options);
+        assert_eq!(columns(&import), [("To Do", vec!["Open"])]);
+        let row = &import.columns[0].rows[0];
+        assert_eq!(row.id, 1);
+        assert_eq!(row.priority, Some('A'));
+        assert_eq!(row.labels, ["now"]);
+        assert_eq!(row.description, "About Open");
+
+        let options = TodoTxtOptions {
+            keep_board: true,
+            ..TodoTxtOptions::default()
+        };
+        assert!(parse(text, &board, &options).columns.is_empty());
+    }
+
+    #[test]
+    fn moves_completed_and_reopened_tasks() {
+        let board = merge_board();
+        let text = "x Open kb:1\nShipped kb:2\n";
+        let import = parse(text, &board, &TodoTxtOptions::default());
+        assert_eq!(
+            columns(&import),
+            [("To Do", vec!["Shipped"]), ("Done", vec!["Open"])]
+        );
+
+        let options = TodoTxtOptions {
+            done_column: Some("Archive".to_string()),
+            ..TodoTxtOptions::default()
+        };
+        let import = parse(text, &board, &options);
+        assert_eq!(
+            columns(&import),
+            [("To Do", vec!["Shipped"]), ("Archive", vec!["Open"])]
+        );
+    }
+
+    #[test]
+    fn adds_new_tasks_and_skips_archived_cards() {
+        let board = merge_board();
+        let text = "x 2024-01-02 Finished\nOld kb:3\n\nNew kb:99\n";
+        let import = parse(text, &board, &TodoTxtOptions::default());
+        assert_eq!(
+            columns(&import),
+            [("To Do", vec!["New"]), ("Done", vec!["Finished"])]
+        );
+        assert_eq!(import.columns[0].rows[0].id, 99);
+        assert_eq!(
+            import.warnings,
+            ["Skipped 1 task(s) whose cards are archived"]
+        );
+    }
+
+    #[test]
+    fn reads_back_labels_with_spaces() {
+        let mut board = board();
+        board.columns[0].rows[0].labels = vec![
+            "next week".to_string(),
+            "@at\thome".to_string(),
+            "100%".to_string(),
+            "%20".to_string(),
+        ];
+        let options = TodoTxtOptions::default();
+        let text = export(&board, &Selection::default(), &options).unwrap();
+        assert!(
+            text.contains(" +next%20week @at%09home +100%25 +%2520 "),
+            "{}",
+            text
+        );
+        assert!(parse(&text, &board, &options).columns.is_empty());
+    }
+
+    #[test]
+    fn keeps_percent_signs_that_encode_nothing() {
+        let task = task("Card +50% +%zz +%+1 +caf%C3%A9", None).unwrap();
+        assert_eq!(task.row.labels, ["50%", "%zz", "%+1", "café"]);
+    }
+}
@@ -0,0 +1,289 @@
+//! Reading boards exported from Trello as JSON, through "Print, export and
+//! share" in the board's menu
+
+use color_eyre::Report;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+use super::Import;
+use crate::app::board::Row;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TrelloBoard {
+    name: String,
+    #[serde(default)]
+    lists: Vec<List>,
+    #[serde(default)]
+    cards: Vec<Card>,
+    #[serde(default)]
+    checklists: Vec<Checklist>,
+    #[serde(default)]
+    actions: Vec<Action>,
+}
+
+#[derive(Deserialize)]
+struct List {
+    id: String,
+    name: String,
+    #[serde(default)]
+    closed: bool,
+    #[serde(default)]
+    pos: f64,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Card {
+    id: String,
+    name: String,
+    #[serde(default)]
+    desc: String,
+    id_list: String,
+    #[serde(default)]
+    closed: bool,
+    #[serde(default)]
+    pos: f64,
+    #[serde(default)]
+    labels: Vec<Label>,
+    due: Option<String>,
+    #[serde(default)]
+    due_complete: bool,
+    #[serde(default)]
+    id_members: Vec<String>,
+    #[serde(default)]
+    attachments: Vec<serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+struct Label {
+    #[serde(default)]
+    name: String,
+    color: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Checklist {
+    id_card: String,
+    name: String,
+    #[serde(default)]
+    pos: f64,
+    #[serde(default)]
+    check_items: Vec<CheckItem>,
+}
+
+#[derive(Deserialize)]
+struct CheckItem {
+    name: String,
+    state: String,
+    #[serde(default)]
+    pos: f64,
+}
+
+#[derive(Deserialize)]
+struct Action {
+    #[serde(rename = "type")]
+    kind: String,
+}
+
+/// Reads a Trello board, with a column for each list. Archived cards are
+/// kept archived, checklists are added to the end of their card's
+/// description, and anything else is reported in the warnings.
+pub fn parse(text: &str) -> color_eyre::Result<Import> {
+    let mut trello: TrelloBoard = serde_json::from_str(text)
+        .map_err(|err| Report::msg(format!("Not a Trello board export: {}", err)))?;
+    let mut import = Import {
+        title: Some(trello.name.clone()),
+        ..Import::default()
+    };
+
+    trello.lists.sort_by(|a, b| a.pos.total_cmp(&b.pos));
+    trello.cards.sort_by(|a, b| a.pos.total_cmp(&b.pos));
+    trello.checklists.sort_by(|a, b| a.pos.total_cmp(&b.pos));
+
+    let lists: HashMap<&str, &List> = trello
+        .lists
+        .iter()
+        .map(|list| (list.id.as_str(), list))
+        .collect();
+    for list in trello.lists.iter().filter(|list| !list.closed) {
+        import.column(&list.name);
+    }
+
+    let mut due_complete = 0;
+    let mut with_members = 0;
+    let mut attachments = 0;
+    let mut missing_list = 0;
+    for card in &trello.cards {
+        let Some(list) = lists.get(card.id_list.as_str()) else {
+            missing_list += 1;
+            continue;
+        };
+        if list.closed {
+            continue;
+        }
+        let mut row = Row::new(card.name.clone());
+        row.description = card.desc.trim_end().to_string();
+        for checklist in trello.checklists.iter().filter(|c| c.id_card == card.id) {
+            add_checklist(&mut row.description, checklist);
+        }
+        row.labels = card.labels.iter().filter_map(label).collect();
+        row.due = card.due.as_ref().map(|due| due.chars().take(10).collect());
+        due_complete += usize::from(card.due_complete);
+        with_members += usize::from(!card.id_members.is_empty());
+        attachments += card.attachments.len();
+
+        let column = import.column(&list.name);
+        if card.closed {
+            column.archived.push(row);
+        } else {
+            column.rows.push(row);
+        }
+    }
+
+    for list in trello.lists.iter().filter(|list| list.closed) {
+        let cards = trello.cards.iter().filter(|c| c.id_list == list.id).count();
+        import.warnings.push(format!(
+            "Skipped the archived list `{}` with {} card(s)",
+            list.name, cards
+        ));
+    }
+    if missing_list > 0 {
+        import.warnings.push(format!(
+            "Skipped {} card(s) whose list isn't in the export",
+            missing_list
+        ));
+    }
+    if due_complete > 0 {
+        import.warnings.push(format!(
+            "{} card(s) had their due date marked complete, which isn't kept",
+            due_complete
+        ));
+    }
+    if with_members > 0 {
+        import.warnings.push(format!(
+            "{} card(s) had members assigned, which aren't kept",
+            with_members
+        ));
+    }
+    if attachments > 0 {
+        import
+            .warnings
+            .push(format!("Skipped {} attachment(s)", attachments));
+    }
+    let comments = trello
+        .actions
+        .iter()
+        .filter(|action| action.kind == "commentCard")
+        .count();
+    if comments > 0 {
+        import
+            .warnings
+            .push(format!("Skipped {} comment(s)", comments));
+    }
+    Ok(import)
+}
+
+/// A label's name as a kb label, using its color if it has no name
+fn label(label: &Label) -> Option<String> {
+    let name = if label.name.trim().is_empty() {
+        label.color.as_deref()?
+    } else {
+        label.name.trim()
+    };
+    Some(name.split_whitespace().collect::<Vec<_>>().join("-"))
+}
+
+/// Adds a checklist to a description as a task list under its name
+fn add_checklist(description: &mut String, checklist: &Checklist) {
+    if !description.is_empty() {
+        description.push_str("\n\n");
+    }
+    description.push_str(&checklist.name);
+    let mut items: Vec<&CheckItem> = checklist.check_items.iter().collect();
+    items.sort_by(|a, b| a.pos.total_cmp(&b.pos));
+    for item in items {
+        let check = if item.state == "complete" { "x" } else { " " };
+        description.push_str(&format!("\n- [{}] {}", check, item.name));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXPORT: &str = r#"{
+        "name": "Roadmap",
+        "lists": [
+            {"id": "l2", "name": "Doing", "pos": 2},
+            {"id": "l1", "name": "Ideas", "pos": 1},
+            {"id": "l3", "name": "Old", "closed": true, "pos": 3}
+        ],
+        "cards": [
+            {"id": "c2", "name": "Second", "idList": "l1", "pos": 2,
+             "labels": [{"name": "big idea"}, {"name": "", "color": "red"}, {"name": ""}],
+             "due": "2024-05-01T12:00:00.000Z", "dueComplete": true},
+            {"id": "c1", "name": "First", "desc": "Notes\n", "idList": "l1", "pos": 1,
+             "idMembers": ["m1"], "attachments": [{}, {}]},
+            {"id": "c3", "name": "Shelved", "idList": "l2", "closed": true},
+            {"id": "c4", "name": "Forgotten", "idList": "l3"},
+            {"id": "c5", "name": "Lost", "idList": "gone"}
+        ],
+        "checklists": [
+            {"idCard": "c1", "name": "Later", "pos": 2,
+             "checkItems": [{"name": "b", "state": "incomplete", "pos": 2},
+                            {"name": "a", "state": "complete", "pos": 1}]},
+            {"idCard": "c1", "name": "Steps", "pos": 1, "checkItems": []}
+        ],
+        "actions": [{"type": "commentCard"}, {"type": "updateCard"}]
+    }"#;
+
+    #[test]
+    fn reads_lists_and_cards_in_order() {
+        let import = parse(EXPORT).unwrap();
+        assert_eq!(import.title.as_deref(), Some("Roadmap"));
+        let titles: Vec<&str> = import
+            .columns
+            .iter()
+            .map(|col| col.title.as_str())
+            .collect();
+        assert_eq!(titles, ["Ideas", "Doing"]);
+
+        let ideas = &import.columns[0];
+        let cards: Vec<&str> = ideas.rows.iter().map(|row| row.title.as_str()).collect();
+        assert_eq!(cards, ["First", "Second"]);
+        assert_eq!(
+            ideas.rows[0].description,
+            "Notes\n\nSteps\n\nLater\n- [x] a\n- [ ] b"
+        );
+        assert_eq!(ideas.rows[1].labels, ["big-idea", "red"]);
+        assert_eq!(ideas.rows[1].due.as_deref(), Some("2024-05-01"));
+
+        let doing = &import.columns[1];
+        assert!(doing.rows.is_empty());
+        assert_eq!(doing.archived[0].title, "Shelved");
+    }
+
+    #[test]
+    fn warns_about_what_isnt_kept() {
+        let import = parse(EXPORT).unwrap();
+        assert_eq!(
+            import.warnings,
+            [
+                "Skipped the archived list `Old` with 1 card(s)",
+                "Skipped 1 card(s) whose list isn't in the export",
+                "1 card(s) had their due date marked complete, which isn't kept",
+                "1 card(s) had members assigned, which aren't kept",
+                "Skipped 2 attachment(s)",
+                "Skipped 1 comment(s)",
+            ]
+        );
+    }
+
+    #[test]
+    fn refuses_other_json() {
+        assert!(parse("{\"title\": \"Not Trello\"}").is_err());
+        assert!(parse("not json").is_err());
+    }
+}
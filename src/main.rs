@@ -1,36 +1,52 @@
 use std::path::Path;
+mod config;
 mod error;
 use color_eyre::Report;
 use std::fs;
 mod app;
+mod commands;
+mod formats;
 mod terminal;
 use app::args::{Cli, Commands};
 use app::model::{Model, Popup};
 mod ui;
-use app::board::Board;
+use app::board::{Board, BOARD_DIR};
+use app::storage::{board_path, default_board_path};
 use clap::Parser;
+use config::Config;
 
 use crossterm::event::{self, Event};
 use tui::{
     backend::Backend,
-    layout::{Alignment, Constraint, Direction, Layout},
-    widgets::Paragraph,
+    layout::{Constraint, Direction, Layout},
     Frame, Terminal,
 };
 use ui::{
-    render_board, render_column_popup, render_dialog, render_help_popup, render_item_popup,
-    render_status_bar,
+    render_archive, render_board, render_column_popup, render_dialog, render_file_picker,
+    render_finder, render_help_popup, render_item_popup, render_move_to, render_palette,
+    render_prompt, render_status_bar, render_tab_bar, Theme,
 };
-const DEFAULT_FILENAME: &str = "kb.json";
 
 fn main() -> color_eyre::Result<()> {
     color_eyre::install()?;
 
     let args = Cli::parse();
-    let board = parse_board(args)?;
+    match &args.command {
+        Some(Commands::Export(args)) => return commands::export(args),
+        Some(Commands::Import(args)) => return commands::import(args),
+        Some(Commands::Sync(args)) => return commands::sync(args),
+        Some(Commands::List(args)) => return commands::list(args),
+        Some(Commands::Show(args)) => return commands::show(args),
+        Some(Commands::Search(args)) => return commands::search(args),
+        Some(Commands::Stats(args)) => return commands::stats(args),
+        Some(Commands::Convert(args)) => return commands::convert(args),
+        _ => {}
+    }
+    let config = Config::load(args.config.as_deref())?;
+    let boards = parse_boards(args)?;
 
     let mut terminal = terminal::init()?;
-    let res = run_app(&mut terminal, board);
+    let res = run_app(&mut terminal, boards, config);
 
     // cleanup - restore terminal
     terminal::reset(&mut terminal)?;
@@ -41,7 +57,7 @@ fn main() -> color_eyre::Result<()> {
     Ok(())
 }
 
-fn get_boolean_input(msg: &str) -> bool {
+pub fn get_boolean_input(msg: &str) -> bool {
     loop {
         let mut buf = String::new();
         println!("{}", msg);
@@ -58,21 +74,21 @@ fn get_boolean_input(msg: &str) -> bool {
 fn get_full_filename(filename: &Option<String>) -> color_eyre::Result<String> {
     match filename {
         Some(f) => {
-            let path = Path::new(".kb");
+            let path = Path::new(BOARD_DIR);
             if !path.exists() || !path.is_dir() {
                 if get_boolean_input(".kb directory not found. Create one? Y/n ") {
-                    _ = fs::create_dir(&path);
+                    _ = fs::create_dir(path);
                 } else {
                     return Err(Report::msg("Failed to find .kb directory"));
                 }
             }
-            Ok(format!(".kb/{}.json", f))
+            Ok(board_path(f))
         }
-        None => Ok(DEFAULT_FILENAME.to_string()),
+        None => Ok(default_board_path()),
     }
 }
 
-fn parse_board<'a>(args: Cli) -> color_eyre::Result<Board> {
+fn parse_boards(args: Cli) -> color_eyre::Result<Vec<Board>> {
     match &args.command {
         Some(Commands::New(arg)) => {
             let filename = get_full_filename(&arg.filename)?;
@@ -84,37 +100,59 @@ fn parse_board<'a>(args: Cli) -> color_eyre::Result<Board> {
             if protected_names.contains(&filename.to_lowercase().as_str()) {
                 return Err(Report::msg("Cannot create board with protected filename"));
             }
-            Board::create(&filename)
+            Ok(vec![Board::create(&filename)?])
         }
+        Some(_) => unreachable!("other commands don't open the TUI"),
         None => {
-            let filename = get_full_filename(&args.filename)?;
-            println!("{}", &filename);
-            let file = fs::read_to_string(&filename)?;
-            Board::from_file(file, filename)
+            let names = if args.filenames.is_empty() {
+                vec![None]
+            } else {
+                args.filenames.into_iter().map(Some).collect()
+            };
+            names
+                .iter()
+                .map(|name| {
+                    let filename = get_full_filename(name)?;
+                    println!("{}", &filename);
+                    Board::load(&filename)
+                })
+                .collect()
         }
     }
 }
 
 struct App<'a> {
     pub model: Model<'a>,
+    pub theme: Theme,
+    pub description_lines: Option<usize>,
 }
 
 impl<'a> App<'a> {
-    fn new(board: Board) -> App<'a> {
+    fn new(boards: Vec<Board>, config: Config) -> App<'a> {
+        let mut model = Model::new(boards, config.keymap);
+        model.system_clipboard = config.system_clipboard;
         App {
-            model: Model::new(board),
+            model,
+            theme: config.theme,
+            description_lines: config.description_lines,
         }
     }
 }
 
-fn run_app<B: Backend>(terminal: &mut Terminal<B>, board: Board) -> color_eyre::Result<()> {
+fn run_app<B: Backend>(
+    terminal: &mut Terminal<B>,
+    boards: Vec<Board>,
+    config: Config,
+) -> color_eyre::Result<()> {
     // create app and run it
-    let mut app = App::new(board);
+    let mut app = App::new(boards, config);
     loop {
         terminal.draw(|f| ui(f, &mut app))?;
 
-        if let Event::Key(key) = event::read()? {
-            app.model.on_keypress(key);
+        match event::read()? {
+            Event::Key(key) => app.model.on_keypress(key),
+            Event::Mouse(mouse) => app.model.on_mouse(mouse),
+            _ => {}
         }
         if app.model.quit {
             return Ok(());
@@ -131,21 +169,37 @@ fn ui<B: Backend>(f: &mut Frame<B>, app: &mut App) {
             Constraint::Length(1),
         ])
         .split(f.size());
-    f.render_widget(
-        Paragraph::new(app.model.board.title()).alignment(Alignment::Center),
-        sections[0],
-    );
+    let theme = &app.theme;
+    render_tab_bar(f, sections[0], &mut app.model, theme);
 
-    render_status_bar(f, sections[2]);
-    render_board(f, sections[1], &mut app.model.board);
+    render_status_bar(
+        f,
+        sections[2],
+        &app.model.keymap,
+        app.model.message.as_deref(),
+        theme,
+    );
+    render_board(
+        f,
+        sections[1],
+        app.model.board(),
+        theme,
+        app.description_lines,
+    );
     match &mut app.model.popup {
-        Popup::CreateRow(state) => render_item_popup(f, "Create Item", state),
-        Popup::EditRow(state) => render_item_popup(f, "Edit Item", state),
-        Popup::DeleteRow(state) => render_dialog(f, "Delete Item?", state),
-        Popup::CreateColumn(state) => render_column_popup(f, "Create Column", state),
-        Popup::EditColumn(state) => render_column_popup(f, "Edit Column", state),
-        Popup::DeleteColumn(state) => render_dialog(f, "Delete Column?", state),
-        Popup::Help => render_help_popup(f),
+        Popup::CreateRow(state) => render_item_popup(f, "Create Item", state, theme),
+        Popup::EditRow(state) => render_item_popup(f, "Edit Item", state, theme),
+        Popup::DeleteRow(state) => render_dialog(f, state, theme),
+        Popup::CreateColumn(state) => render_column_popup(f, "Create Column", state, theme),
+        Popup::EditColumn(state) => render_column_popup(f, "Edit Column", state, theme),
+        Popup::DeleteColumn(state) => render_dialog(f, state, theme),
+        Popup::Help(scroll) => render_help_popup(f, &app.model.keymap, scroll, theme),
+        Popup::Finder(state) => render_finder(f, state, theme),
+        Popup::Palette(state) => render_palette(f, state, theme),
+        Popup::FilePicker(state) => render_file_picker(f, state, theme),
+        Popup::Prompt(state) => render_prompt(f, state, theme),
+        Popup::MoveTo(state) => render_move_to(f, state, theme),
+        Popup::Archive(state) => render_archive(f, state, theme),
         Popup::None => {}
     };
 }
@@ -0,0 +1,71 @@
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout},
+    style::Modifier,
+    text::{Span, Spans, Text},
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table, Wrap},
+    Frame,
+};
+
+use crate::app::ArchiveState;
+
+use super::{
+    popup::{render_popup, render_text_area},
+    Theme,
+};
+
+pub fn render_archive<B: Backend>(f: &mut Frame<B>, state: &mut ArchiveState, theme: &Theme) {
+    let frame = render_popup(f, "Archived Items", 20, None, theme);
+    let sections = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Min(1),
+            Constraint::Length(1),
+        ])
+        .split(frame);
+    let list = &mut state.list;
+    render_text_area(f, list.field, &mut list.query, true, sections[0], theme);
+    f.render_widget(
+        Paragraph::new("Enter puts the card back in its column").style(theme.muted),
+        sections[2],
+    );
+
+    let panes = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(sections[1]);
+
+    let rows: Vec<Row> = list
+        .matches()
+        .map(|entry| {
+            let text = Text::from(vec![
+                Spans::from(Span::raw(entry.title.clone())),
+                Spans::from(Span::styled(
+                    entry.column.clone(),
+                    theme.muted.add_modifier(Modifier::ITALIC),
+                )),
+            ]);
+            Row::new(vec![Cell::from(text)]).height(2)
+        })
+        .collect();
+    let t = Table::new(rows)
+        .highlight_style(theme.selected.add_modifier(Modifier::BOLD))
+        .widths(&[Constraint::Percentage(100)]);
+    f.render_stateful_widget(t, panes[0], &mut list.state);
+
+    let Some(entry) = list.selected() else { return };
+    let mut preview = Text::styled(entry.title.clone(), theme.title);
+    preview.extend(Text::styled(
+        format!("#{} · {}", entry.id, entry.column),
+        theme.muted,
+    ));
+    preview.extend(Text::raw("\n"));
+    preview.extend(Text::styled(entry.description.clone(), theme.description));
+    f.render_widget(
+        Paragraph::new(preview)
+            .block(Block::default().borders(Borders::LEFT))
+            .wrap(Wrap { trim: false }),
+        panes[1],
+    );
+}
@@ -1,80 +1,407 @@
-use crate::app::board::Board;
+use crate::app::board::{Board, Column, Density, MIN_COLUMN_WIDTH};
+
+use super::{
+    wrap::{truncate, wrap},
+    Theme,
+};
+
+use unicode_width::UnicodeWidthStr;
 
 use tui::{
     backend::Backend,
-    layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
-    text::Text,
-    widgets::{Block, Borders, Cell, Row as TuiRow, Table},
+    layout::{Alignment, Constraint, Rect},
+    text::{Span, Spans, Text},
+    widgets::{Block, Borders, Cell, Paragraph, Row as TuiRow, Table, TableState},
     Frame,
 };
 
-fn wrap_string(str: &str, length: usize) -> String {
-    str.chars()
-        .enumerate()
-        .flat_map(|(i, c)| {
-            if i != 0 && i % length == 0 {
-                Some('\n')
-            } else {
-                None
+/// Drawn before the title of marked cards
+const MARKER: &str = "● ";
+
+/// Works out which rows fit in a column of `max_height`, starting from
+/// `offset` and scrolling as little as possible to keep `selected` in view.
+/// Mirrors how `Table` lays out its rows, including the `margin` below each
+/// row, except that the selected row is shown even if it doesn't fit.
+fn visible_rows(
+    heights: &[u16],
+    margin: u16,
+    offset: usize,
+    selected: Option<usize>,
+    max_height: u16,
+) -> (usize, usize) {
+    if heights.is_empty() {
+        return (0, 0);
+    }
+    let total_height = |i: usize| heights[i] + margin;
+    let offset = offset.min(heights.len() - 1);
+    let (mut start, mut end, mut height) = (offset, offset, 0u16);
+    for &row_height in &heights[offset..] {
+        if height + row_height > max_height {
+            break;
+        }
+        height += row_height + margin;
+        end += 1;
+    }
+
+    let selected = selected.unwrap_or(0).min(heights.len() - 1);
+    while selected >= end {
+        height = height.saturating_add(total_height(end));
+        end += 1;
+        while height > max_height {
+            height = height.saturating_sub(total_height(start));
+            start += 1;
+        }
+    }
+    while selected < start {
+        start -= 1;
+        height = height.saturating_add(total_height(start));
+        while height > max_height {
+            end -= 1;
+            height = height.saturating_sub(total_height(end));
+        }
+    }
+    // A card too tall for the column on its own is still shown, cut short
+    if !(start..end).contains(&selected) {
+        return (selected, selected + 1);
+    }
+    (start, end)
+}
+
+/// Works out which columns fit side by side in `max_width`, starting from
+/// `offset` and scrolling as little as possible to keep `selected` in view.
+/// At least one column is always shown.
+fn visible_columns(
+    widths: &[u16],
+    offset: usize,
+    selected: usize,
+    max_width: u16,
+) -> (usize, usize) {
+    let selected = selected.min(widths.len() - 1);
+    let mut start = offset.min(selected);
+    loop {
+        let mut end = start;
+        let mut used = 0u16;
+        while end < widths.len() && used.saturating_add(widths[end]) <= max_width {
+            used += widths[end];
+            end += 1;
+        }
+        let end = end.max(start + 1);
+        if selected < end {
+            return (start, end);
+        }
+        start += 1;
+    }
+}
+
+/// Splits the board area between the columns in view. Columns without a
+/// width of their own share whatever space the others leave.
+fn column_rects(board: &mut Board, rect: Rect) -> Vec<(usize, Rect)> {
+    let min_widths: Vec<u16> = board
+        .columns
+        .iter()
+        .map(|col| col.fixed_width().unwrap_or(MIN_COLUMN_WIDTH))
+        .collect();
+    let (start, end) = visible_columns(
+        &min_widths,
+        board.column_offset,
+        board.selected_column,
+        rect.width,
+    );
+    board.column_offset = start;
+
+    let visible = &board.columns[start..end];
+    let fixed: u16 = visible.iter().filter_map(|col| col.fixed_width()).sum();
+    let flexible = visible
+        .iter()
+        .filter(|col| col.fixed_width().is_none())
+        .count() as u16;
+    let mut spare = rect.width.saturating_sub(fixed);
+
+    let mut x = rect.x;
+    let mut remaining_flexible = flexible;
+    (start..end)
+        .map(|i| {
+            let width = match board.columns[i].fixed_width() {
+                Some(width) => width,
+                None => {
+                    let width = spare / remaining_flexible;
+                    spare -= width;
+                    remaining_flexible -= 1;
+                    width
+                }
             }
-            .into_iter()
-            .chain(std::iter::once(c))
+            .min(rect.right().saturating_sub(x));
+            let column = Rect::new(x, rect.y, width, rect.height);
+            x += width;
+            (i, column)
         })
-        .collect::<String>()
+        .collect()
 }
 
-pub fn render_board<B: Backend>(f: &mut Frame<B>, rect: Rect, board: &mut Board) {
-    if board.columns.len() == 0 {
+/// Draws a collapsed column as a thin strip with its card count followed by
+/// its title running downwards
+fn render_collapsed_column<B: Backend>(
+    f: &mut Frame<B>,
+    rect: Rect,
+    col: &mut Column,
+    selected: bool,
+    theme: &Theme,
+) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(tui::widgets::BorderType::Rounded)
+        .border_style(if selected {
+            theme.border_selected
+        } else {
+            theme.border
+        });
+    let inner = block.inner(rect);
+    col.area = inner;
+
+    let mut text = Text::styled(col.rows.len().to_string(), theme.muted);
+    text.extend(Text::raw("\n"));
+    text.extend(Text::styled(
+        col.title
+            .chars()
+            .map(|c| c.to_string())
+            .collect::<Vec<_>>()
+            .join("\n"),
+        theme.title,
+    ));
+    f.render_widget(Paragraph::new(text).block(block), rect);
+}
+
+/// Renders the board's columns side by side. How much of each card is shown
+/// depends on the board's density, descriptions longer than
+/// `description_lines` are always cut short.
+pub fn render_board<B: Backend>(
+    f: &mut Frame<B>,
+    rect: Rect,
+    board: &mut Board,
+    theme: &Theme,
+    description_lines: Option<usize>,
+) {
+    if board.columns.is_empty() {
         return;
     }
+    let description_lines = match board.density {
+        Density::Compact => Some(0),
+        Density::Summary => Some(1),
+        Density::Full => description_lines,
+    };
+    // Cards showing just their titles don't need spacing between them
+    let margin = if board.density == Density::Compact {
+        0
+    } else {
+        1
+    };
 
-    let width = (100 / board.columns.len()) as u16;
-    let rects = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints(vec![Constraint::Percentage(width); board.columns.len()].as_ref())
-        .split(rect);
-
-    board.columns.iter_mut().enumerate().for_each(|(i, col)| {
-        let rect_width: usize = rects[i].width as usize - 2;
-        let selected_style = Style::default().fg(Color::Green);
-        let rows = col.rows.iter().map(|row| {
-            let title = wrap_string(&row.title, rect_width as usize);
-            let description = row
-                .description
-                .lines()
-                .map(|str| wrap_string(str, rect_width))
-                .collect::<Vec<String>>()
-                .join("\n");
-            let height = description.lines().count() + title.lines().count();
-            let mut text = Text::styled(title, Style::default().add_modifier(Modifier::BOLD));
-            text.extend(Text::styled(
-                description,
-                Style::default().add_modifier(Modifier::ITALIC | Modifier::DIM),
-            ));
-            let cell = Cell::from(text);
-            TuiRow::new(vec![cell])
-                .height(height as u16)
-                .bottom_margin(1)
-        });
-        let t = Table::new(rows)
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .border_type(tui::widgets::BorderType::Rounded)
-                    .border_style(Style::default().fg(Color::Blue).add_modifier(
-                        if board.selected_column == i {
-                            Modifier::empty()
-                        } else {
-                            Modifier::DIM
-                        },
-                    ))
-                    .title(col.title.clone())
-                    .title_alignment(tui::layout::Alignment::Center),
-            )
-            .highlight_style(selected_style)
-            // .highlight_symbol("│")
-            .widths(&[Constraint::Percentage(100)]);
-        f.render_stateful_widget(t, rects[i], &mut col.state);
-    });
+    let rects = column_rects(board, rect);
+    for col in board.columns.iter_mut() {
+        col.area = Rect::default();
+        col.row_areas.clear();
+    }
+
+    let density = board.density;
+    for &(i, column_rect) in &rects {
+        let col = &mut board.columns[i];
+        if col.collapsed {
+            render_collapsed_column(f, column_rect, col, board.selected_column == i, theme);
+            continue;
+        }
+        let rect_width = (column_rect.width as usize).saturating_sub(2).max(1);
+        let marked: Vec<bool> = board.columns[i]
+            .rows
+            .iter()
+            .map(|row| board.is_marked(row.id))
+            .collect();
+        let col = &mut board.columns[i];
+        let rows: Vec<(TuiRow, u16)> = col
+            .rows
+            .iter()
+            .zip(marked)
+            .map(|(row, marked)| {
+                let title = match row.priority {
+                    Some(priority) => format!("({}) {}", priority, row.title),
+                    None => row.title.clone(),
+                };
+                // Marked cards are indented to make room for the marker
+                let indent = if marked { MARKER.width() } else { 0 };
+                let title = wrap(&title, rect_width.saturating_sub(indent).max(1));
+                let labels = if (row.labels.is_empty() && row.due.is_none())
+                    || density == Density::Compact
+                {
+                    Vec::new()
+                } else {
+                    let labels: Vec<String> = row
+                        .due
+                        .iter()
+                        .map(|due| format!("due {}", due))
+                        .chain(row.labels.iter().map(|label| format!("#{}", label)))
+                        .collect();
+                    wrap(&labels.join(" "), rect_width)
+                };
+                let mut description = wrap(&row.description, rect_width);
+                if let Some(max_lines) = description_lines {
+                    description = truncate(description, max_lines, rect_width);
+                }
+                let height = title.len() + labels.len() + description.len();
+                let lines: Vec<Spans> = title
+                    .into_iter()
+                    .enumerate()
+                    .map(|(n, line)| {
+                        let mut spans = Vec::new();
+                        if marked {
+                            let marker = if n == 0 { MARKER } else { "  " };
+                            spans.push(Span::styled(marker, theme.marked));
+                        }
+                        spans.push(Span::styled(line, theme.title));
+                        Spans::from(spans)
+                    })
+                    .chain(
+                        labels
+                            .into_iter()
+                            .map(|line| Spans::from(Span::styled(line, theme.label))),
+                    )
+                    .chain(
+                        description
+                            .into_iter()
+                            .map(|line| Spans::from(Span::styled(line, theme.description))),
+                    )
+                    .collect();
+                let cell = Cell::from(Text::from(lines));
+                let row = TuiRow::new(vec![cell])
+                    .height(height as u16)
+                    .bottom_margin(margin);
+                (row, height as u16)
+            })
+            .collect();
+
+        // Only the visible rows are handed to the table, so that the layout
+        // can be recorded for mouse clicks
+        let border_style = if board.selected_column == i {
+            theme.border_selected
+        } else {
+            theme.border
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(tui::widgets::BorderType::Rounded)
+            .border_style(border_style)
+            .title(col.title.clone())
+            .title_alignment(Alignment::Center);
+        let inner = block.inner(column_rect);
+        let heights: Vec<u16> = rows.iter().map(|(_, height)| *height).collect();
+        let (start, end) = visible_rows(
+            &heights,
+            margin,
+            col.offset,
+            col.state.selected(),
+            inner.height,
+        );
+        col.offset = start;
+        col.area = inner;
+        col.row_areas.clear();
+        let mut y = inner.y;
+        for (index, height) in heights.iter().enumerate().take(end).skip(start) {
+            let height = (*height).min(inner.bottom().saturating_sub(y));
+            col.row_areas
+                .push((index, Rect::new(inner.x, y, inner.width, height)));
+            y = y.saturating_add(height + margin);
+        }
+
+        let mut state = TableState::default();
+        state.select(col.state.selected().map(|selected| selected - start));
+        let t = Table::new(
+            rows.into_iter()
+                .skip(start)
+                .take(end - start)
+                // The table leaves out rows taller than it
+                .map(|(row, height)| row.height(height.min(inner.height))),
+        )
+        .block(block)
+        .highlight_style(theme.selected)
+        // .highlight_symbol("│")
+        .widths(&[Constraint::Percentage(100)]);
+        f.render_stateful_widget(t, column_rect, &mut state);
+
+        if start > 0 || end < col.rows.len() {
+            let position = format!(
+                " {}{}/{}{} ",
+                if start > 0 { "▲ " } else { "" },
+                col.state.selected().unwrap_or(start) + 1,
+                col.rows.len(),
+                if end < col.rows.len() { " ▼" } else { "" },
+            );
+            let border = Rect::new(
+                column_rect.x + 1,
+                column_rect.bottom().saturating_sub(1),
+                column_rect.width.saturating_sub(2),
+                1,
+            );
+            f.render_widget(
+                Paragraph::new(position)
+                    .style(border_style)
+                    .alignment(Alignment::Right),
+                border,
+            );
+        }
+    }
+
+    // Arrows on the outer borders show there are more columns out of view
+    let indicator = |x: u16| Rect::new(x, rect.y + rect.height / 2, 1, 1);
+    if let Some(&(first, _)) = rects.first() {
+        if first > 0 {
+            f.render_widget(
+                Paragraph::new("◀").style(theme.border_selected),
+                indicator(rect.x),
+            );
+        }
+    }
+    if let Some(&(last, last_rect)) = rects.last() {
+        if last + 1 < board.columns.len() {
+            f.render_widget(
+                Paragraph::new("▶").style(theme.border_selected),
+                indicator(last_rect.right().saturating_sub(1)),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shows_a_card_taller_than_the_column() {
+        assert_eq!(visible_rows(&[10], 0, 0, Some(0), 5), (0, 1));
+        assert_eq!(visible_rows(&[2, 10], 1, 0, Some(1), 5), (1, 2));
+    }
+
+    #[test]
+    fn scrolls_to_the_selected_card() {
+        assert_eq!(visible_rows(&[2, 2, 2, 2], 1, 0, Some(3), 6), (2, 4));
+    }
+
+    #[test]
+    fn shows_as_many_columns_as_fit() {
+        assert_eq!(visible_columns(&[24, 24, 24, 24], 0, 0, 60), (0, 2));
+        assert_eq!(visible_columns(&[24, 24, 24, 24], 0, 1, 96), (0, 4));
+        assert_eq!(visible_columns(&[10, 30, 10], 0, 0, 45), (0, 2));
+    }
+
+    #[test]
+    fn scrolls_as_little_as_possible() {
+        assert_eq!(visible_columns(&[24, 24, 24, 24], 0, 2, 60), (1, 3));
+        assert_eq!(visible_columns(&[24, 24, 24, 24], 1, 3, 60), (2, 4));
+        assert_eq!(visible_columns(&[24, 24, 24, 24], 2, 0, 60), (0, 2));
+        assert_eq!(visible_columns(&[24, 24, 24, 24], 1, 2, 60), (1, 3));
+    }
+
+    #[test]
+    fn shows_a_column_wider_than_the_board() {
+        assert_eq!(visible_columns(&[100], 0, 0, 50), (0, 1));
+        assert_eq!(visible_columns(&[30, 100, 30], 0, 1, 50), (1, 2));
+        assert_eq!(visible_columns(&[24, 24], 0, 9, 60), (0, 2));
+    }
 }
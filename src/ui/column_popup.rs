@@ -6,17 +6,28 @@ use tui::{
 
 use crate::app::{ColumnFields, ColumnPopupState};
 
-use super::popup::{render_popup, render_text_area};
+use super::{
+    popup::{render_popup, render_text_area},
+    Theme,
+};
 
 pub fn render_column_popup<B: Backend>(
     f: &mut Frame<B>,
     title: &str,
     state: &mut ColumnPopupState,
+    theme: &Theme,
 ) {
-    let frame = render_popup(f, title, 6, None);
+    let frame = render_popup(f, title, 6, None, theme);
     let sections = Layout::default()
         .direction(Direction::Vertical)
         .constraints([Constraint::Length(3), Constraint::Length(1)])
         .split(frame);
-    render_text_area(f, ColumnFields::Title, &mut state.title, true, sections[0]);
+    render_text_area(
+        f,
+        ColumnFields::Title,
+        &mut state.title,
+        true,
+        sections[0],
+        theme,
+    );
 }
@@ -6,10 +6,18 @@ use tui::{
 
 use crate::app::{RowFields, RowPopupState};
 
-use super::popup::{render_popup, render_text_area};
+use super::{
+    popup::{render_popup, render_text_area},
+    Theme,
+};
 
-pub fn render_item_popup<B: Backend>(f: &mut Frame<B>, title: &str, state: &mut RowPopupState) {
-    let frame = render_popup(f, title, 12, None);
+pub fn render_item_popup<B: Backend>(
+    f: &mut Frame<B>,
+    title: &str,
+    state: &mut RowPopupState,
+    theme: &Theme,
+) {
+    let frame = render_popup(f, title, 12, None, theme);
     let sections = Layout::default()
         .direction(Direction::Vertical)
         .constraints([Constraint::Length(3), Constraint::Min(1)])
@@ -20,6 +28,7 @@ pub fn render_item_popup<B: Backend>(f: &mut Frame<B>, title: &str, state: &mut
         &mut state.title,
         state.focussed == RowFields::Title,
         sections[0],
+        theme,
     );
     render_text_area(
         f,
@@ -27,5 +36,6 @@ pub fn render_item_popup<B: Backend>(f: &mut Frame<B>, title: &str, state: &mut
         &mut state.description,
         state.focussed == RowFields::Description,
         sections[1],
+        theme,
     );
 }
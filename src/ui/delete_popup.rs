@@ -1,7 +1,6 @@
 use tui::{
     backend::Backend,
     layout::{Alignment, Constraint, Direction, Layout},
-    style::{Color, Modifier, Style},
     text::Span,
     widgets::Paragraph,
     Frame,
@@ -9,10 +8,10 @@ use tui::{
 
 use crate::app::{DialogFields, DialogState, PopupFields};
 
-use super::popup::render_popup;
+use super::{popup::render_popup, Theme};
 
-pub fn render_dialog<B: Backend>(f: &mut Frame<B>, message: &str, state: &mut DialogState) {
-    let frame = render_popup(f, "", 7, Some(30));
+pub fn render_dialog<B: Backend>(f: &mut Frame<B>, state: &mut DialogState, theme: &Theme) {
+    let frame = render_popup(f, "", 7, Some(30), theme);
 
     let sections = Layout::default()
         .direction(Direction::Vertical)
@@ -20,7 +19,7 @@ pub fn render_dialog<B: Backend>(f: &mut Frame<B>, message: &str, state: &mut Di
         .split(frame);
 
     f.render_widget(
-        Paragraph::new(message).alignment(Alignment::Center),
+        Paragraph::new(state.message.as_str()).alignment(Alignment::Center),
         sections[1],
     );
 
@@ -28,26 +27,35 @@ pub fn render_dialog<B: Backend>(f: &mut Frame<B>, message: &str, state: &mut Di
         .direction(Direction::Horizontal)
         .constraints([Constraint::Percentage(50); 2])
         .split(sections[3]);
+    state.cancel_area = button_sections[0];
+    state.confirm_area = button_sections[1];
     f.render_widget(
-        button_widget(state.focussed == DialogFields::Cancel, DialogFields::Cancel),
+        button_widget(
+            state.focussed == DialogFields::Cancel,
+            DialogFields::Cancel,
+            theme,
+        ),
         button_sections[0],
     );
     f.render_widget(
         button_widget(
             state.focussed == DialogFields::Confirm,
             DialogFields::Confirm,
+            theme,
         ),
         button_sections[1],
     );
 }
 
-pub fn button_widget<F: PopupFields>(focussed: bool, field: F) -> Paragraph<'static> {
+pub fn button_widget<F: PopupFields>(
+    focussed: bool,
+    field: F,
+    theme: &Theme,
+) -> Paragraph<'static> {
     let style = if focussed {
-        Style::default()
-            .add_modifier(Modifier::BOLD)
-            .bg(Color::Magenta)
+        theme.button_focused
     } else {
-        Style::default().bg(Color::DarkGray)
+        theme.button
     };
 
     Paragraph::new(Span::styled(field.title().to_string(), style)).alignment(Alignment::Center)
@@ -1,8 +1,7 @@
-#![allow(dead_code)]
 use tui::{
     backend::Backend,
     layout::Constraint,
-    style::{Color, Modifier, Style},
+    style::Style,
     text::Text,
     widgets::{Cell, Row, Table},
     Frame,
@@ -10,10 +9,14 @@ use tui::{
 
 use crate::app::FilePickerState;
 
-use super::popup::render_popup;
+use super::{popup::render_popup, Theme};
 
-pub fn render_file_picker<B: Backend>(f: &mut Frame<B>, state: &mut FilePickerState) {
-    let popup = render_popup(f, "Select Board", 20, None);
+pub fn render_file_picker<B: Backend>(
+    f: &mut Frame<B>,
+    state: &mut FilePickerState,
+    theme: &Theme,
+) {
+    let popup = render_popup(f, "Select Board", 20, None, theme);
     let rows = state.files.iter().map(|row| {
         let text = Text::styled(row, Style::default());
         let cell = Cell::from(text);
@@ -28,11 +31,7 @@ pub fn render_file_picker<B: Backend>(f: &mut Frame<B>, state: &mut FilePickerSt
         //         .title("Select Board")
         //         .title_alignment(tui::layout::Alignment::Center),
         // )
-        .highlight_style(
-            Style::default()
-                .fg(Color::Blue)
-                .add_modifier(Modifier::BOLD),
-        )
+        .highlight_style(theme.selected)
         .widths(&[Constraint::Percentage(100)]);
     f.render_stateful_widget(t, popup, &mut state.state);
 }
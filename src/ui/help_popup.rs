@@ -1,48 +1,54 @@
 use tui::{
     backend::Backend,
-    layout::{Constraint, Direction, Layout},
-    style::{Modifier, Style},
-    text::Text,
-    widgets::{List, ListItem},
+    style::Modifier,
+    text::{Span, Spans},
+    widgets::Paragraph,
     Frame,
 };
 
-use super::popup::render_popup;
+use crate::app::{Action, Keymap};
 
-pub fn render_help_popup<B: Backend>(f: &mut Frame<B>) {
-    let items = vec![
-        ("Navigate", "↑↓←→"),
-        ("Move Item", "⇧ ↑↓←→"),
-        ("Create Item", "c"),
-        ("Edit Item", "e"),
-        ("Delete Item", "d"),
-        ("Create Column", "⇧c"),
-        ("Edit Column", "⇧e"),
-        ("Delete Column", "⇧d"),
-    ];
-    let max_cmd_width = 6;
-    let frame = render_popup(f, "Help", items.len() as u16 + 4, None);
-    let sections = Layout::default()
-        .margin(1)
-        .direction(Direction::Horizontal)
-        .constraints([Constraint::Min(1), Constraint::Length(max_cmd_width)])
-        .split(frame);
-    let descriptions: Vec<ListItem> = items
+use super::{popup::render_popup, Theme};
+
+/// Lists every action with its keys. The list scrolls when it doesn't fit,
+/// `scroll` is kept in range.
+pub fn render_help_popup<B: Backend>(
+    f: &mut Frame<B>,
+    keymap: &Keymap,
+    scroll: &mut u16,
+    theme: &Theme,
+) {
+    let items: Vec<(&str, String)> = Action::ALL
         .iter()
-        .map(|(description, _)| ListItem::new(Text::from(*description)))
+        .map(|action| (action.title(), keymap.keys(*action)))
         .collect();
-    let description_list = List::new(descriptions);
-    f.render_widget(description_list, sections[0]);
+    let max_cmd_width = items
+        .iter()
+        .map(|(_, command)| command.chars().count())
+        .max()
+        .unwrap_or(0);
+    let height = (items.len() as u16 + 4).min(f.size().height);
+    let mut frame = render_popup(f, "Help", height, None, theme);
+    frame.x += 1;
+    frame.width = frame.width.saturating_sub(2);
+    frame.y += 1;
+    frame.height = frame.height.saturating_sub(2);
 
-    let commands: Vec<ListItem> = items
+    let width = frame.width as usize;
+    let lines: Vec<Spans> = items
         .iter()
-        .map(|(_, command)| {
-            ListItem::new(Text::styled(
-                format!("{:>6}", command),
-                Style::default().add_modifier(Modifier::BOLD),
-            ))
+        .map(|(description, command)| {
+            let padding = width.saturating_sub(description.chars().count() + max_cmd_width);
+            Spans::from(vec![
+                Span::raw(*description),
+                Span::raw(" ".repeat(padding)),
+                Span::styled(
+                    format!("{:>width$}", command, width = max_cmd_width),
+                    theme.popup.add_modifier(Modifier::BOLD),
+                ),
+            ])
         })
         .collect();
-    let commands_list = List::new(commands);
-    f.render_widget(commands_list, sections[1]);
+    *scroll = (*scroll).min((lines.len() as u16).saturating_sub(frame.height));
+    f.render_widget(Paragraph::new(lines).scroll((*scroll, 0)), frame);
 }
@@ -1,16 +1,31 @@
+mod archive;
 mod board;
 mod column_popup;
 mod create_popup;
 mod delete_popup;
 mod file_picker;
+mod finder;
 mod help_popup;
+mod move_to;
+mod palette;
 mod popup;
+mod prompt;
 mod status_bar;
+mod tab_bar;
+mod theme;
+mod wrap;
 
+pub use self::archive::render_archive;
 pub use self::board::render_board;
 pub use self::column_popup::render_column_popup;
 pub use self::create_popup::render_item_popup;
 pub use self::delete_popup::render_dialog;
 pub use self::file_picker::render_file_picker;
+pub use self::finder::render_finder;
 pub use self::help_popup::render_help_popup;
+pub use self::move_to::render_move_to;
+pub use self::palette::render_palette;
+pub use self::prompt::render_prompt;
 pub use self::status_bar::render_status_bar;
+pub use self::tab_bar::render_tab_bar;
+pub use self::theme::{parse_color, parse_modifier, Theme};
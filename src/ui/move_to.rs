@@ -0,0 +1,65 @@
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout},
+    style::Modifier,
+    text::Text,
+    widgets::{Cell, Paragraph, Row, Table},
+    Frame,
+};
+
+use crate::app::MoveToState;
+
+use super::{
+    popup::{render_popup, render_text_area},
+    Theme,
+};
+
+pub fn render_move_to<B: Backend>(f: &mut Frame<B>, state: &mut MoveToState, theme: &Theme) {
+    let frame = render_popup(f, "Move To Column", 14, None, theme);
+    let sections = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Min(1),
+            Constraint::Length(1),
+        ])
+        .split(frame);
+    let list = &mut state.list;
+    render_text_area(
+        f,
+        list.field,
+        &mut list.query,
+        true,
+        sections[0],
+        theme,
+    );
+
+    let rows: Vec<Row> = list
+        .matches()
+        .map(|entry| {
+            let number = entry.number.map(|n| n.to_string()).unwrap_or_default();
+            Row::new(vec![
+                Cell::from(Text::styled(number, theme.muted)),
+                Cell::from(entry.title.clone()),
+            ])
+        })
+        .collect();
+    let widths = [
+        Constraint::Length(3),
+        Constraint::Length(sections[1].width.saturating_sub(4)),
+    ];
+    let t = Table::new(rows)
+        .highlight_style(theme.selected.add_modifier(Modifier::BOLD))
+        .widths(&widths);
+    f.render_stateful_widget(t, sections[1], &mut list.state);
+
+    let position = if state.top { "top" } else { "bottom" };
+    f.render_widget(
+        Paragraph::new(format!(
+            "Moving to the {} of the column, Tab to change",
+            position
+        ))
+        .style(theme.muted),
+        sections[2],
+    );
+}
@@ -0,0 +1,52 @@
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout},
+    style::Modifier,
+    text::Text,
+    widgets::{Cell, Row, Table},
+    Frame,
+};
+
+use crate::app::PaletteState;
+
+use super::{
+    popup::{render_popup, render_text_area},
+    Theme,
+};
+
+pub fn render_palette<B: Backend>(f: &mut Frame<B>, state: &mut PaletteState, theme: &Theme) {
+    let frame = render_popup(f, "Commands", 16, None, theme);
+    let sections = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(1)])
+        .split(frame);
+    let list = &mut state.list;
+    render_text_area(f, list.field, &mut list.query, true, sections[0], theme);
+
+    let max_keys_width = list
+        .entries()
+        .iter()
+        .map(|entry| entry.keys.chars().count())
+        .max()
+        .unwrap_or(0) as u16;
+    let rows: Vec<Row> = list
+        .matches()
+        .map(|entry| {
+            Row::new(vec![
+                Cell::from(entry.action.title().to_string()),
+                Cell::from(Text::styled(
+                    format!("{:>width$}", entry.keys, width = max_keys_width as usize),
+                    theme.muted,
+                )),
+            ])
+        })
+        .collect();
+    let widths = [
+        Constraint::Length(sections[1].width.saturating_sub(max_keys_width + 1)),
+        Constraint::Length(max_keys_width),
+    ];
+    let t = Table::new(rows)
+        .highlight_style(theme.selected.add_modifier(Modifier::BOLD))
+        .widths(&widths);
+    f.render_stateful_widget(t, sections[1], &mut list.state);
+}
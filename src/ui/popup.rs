@@ -1,7 +1,7 @@
 use tui::{
     backend::Backend,
     layout::{Alignment, Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     widgets::{Block, BorderType, Borders, Clear, Paragraph},
     Frame,
 };
@@ -9,18 +9,21 @@ use tui_textarea::TextArea;
 
 use crate::app::PopupFields;
 
+use super::Theme;
+
 pub fn render_popup<B: Backend>(
     f: &mut Frame<B>,
     title: &str,
     height: u16,
     width: Option<u16>,
+    theme: &Theme,
 ) -> Rect {
     let constraints = {
         if let Some(width) = width {
             [
-                Constraint::Length((f.size().width - width) / 2),
+                Constraint::Length(f.size().width.saturating_sub(width) / 2),
                 Constraint::Length(width),
-                Constraint::Length((f.size().width - width) / 2),
+                Constraint::Length(f.size().width.saturating_sub(width) / 2),
             ]
         } else {
             // Popup takes up 60% of the view's width
@@ -37,7 +40,7 @@ pub fn render_popup<B: Backend>(
         .constraints(constraints)
         .split(f.size())[1];
 
-    let spacing = (f.size().height - height) / 2;
+    let spacing = f.size().height.saturating_sub(height) / 2;
     let popup_layout = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -52,7 +55,8 @@ pub fn render_popup<B: Backend>(
         .title(title)
         .title_alignment(Alignment::Center)
         .borders(Borders::ALL)
-        .border_type(BorderType::Rounded);
+        .border_type(BorderType::Rounded)
+        .style(theme.popup);
     let popup_inner = popup.inner(popup_layout);
     f.render_widget(popup, popup_layout);
     popup_inner
@@ -64,6 +68,7 @@ pub fn render_text_area<B: Backend, F: PopupFields>(
     state: &mut TextArea,
     focussed: bool,
     rect: Rect,
+    theme: &Theme,
 ) {
     let cursor_style = if focussed {
         Style::default().add_modifier(Modifier::REVERSED)
@@ -73,13 +78,7 @@ pub fn render_text_area<B: Backend, F: PopupFields>(
     state.set_cursor_style(cursor_style);
     state.set_cursor_line_style(Style::default());
 
-    let block_style = if focussed {
-        Style::default().fg(Color::Green)
-    } else {
-        Style::default()
-            .fg(Color::Reset)
-            .add_modifier(Modifier::DIM)
-    };
+    let block_style = if focussed { theme.focus } else { theme.muted };
 
     let block = Block::default()
         .title(field.title())
@@ -90,10 +89,10 @@ pub fn render_text_area<B: Backend, F: PopupFields>(
     f.render_widget(block, rect);
 
     if let Some(first) = state.lines().first() {
-        if first.len() == 0 && state.lines().len() == 1 {
+        if first.is_empty() && state.lines().len() == 1 {
             f.render_widget(
                 Paragraph::new(field.placeholder())
-                    .style(Style::default().add_modifier(Modifier::DIM | Modifier::ITALIC)),
+                    .style(theme.muted.add_modifier(Modifier::ITALIC)),
                 inner_rect,
             );
         } else {
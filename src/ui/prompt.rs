@@ -0,0 +1,25 @@
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout},
+    Frame,
+};
+
+use crate::app::{PromptFields, PromptState};
+
+use super::{
+    popup::{render_popup, render_text_area},
+    Theme,
+};
+
+pub fn render_prompt<B: Backend>(f: &mut Frame<B>, state: &mut PromptState, theme: &Theme) {
+    let title = match state.field {
+        PromptFields::Labels => "Add/Remove Labels",
+        PromptFields::Priority => "Set Priority",
+    };
+    let frame = render_popup(f, title, 6, None, theme);
+    let sections = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Length(1)])
+        .split(frame);
+    render_text_area(f, state.field, &mut state.input, true, sections[0], theme);
+}
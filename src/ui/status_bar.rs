@@ -1,16 +1,46 @@
 use tui::{
     backend::Backend,
     layout::{Alignment, Rect},
-    style::{Modifier, Style},
     widgets::Paragraph,
     Frame,
 };
 
-/// Renders the one-line status bar at the bottom of the board
-pub fn render_status_bar<B: Backend>(f: &mut Frame<B>, rect: Rect) {
+use crate::app::{Action, Keymap};
+
+use super::Theme;
+
+/// Renders the one-line status bar at the bottom of the board, showing
+/// `message` instead of key hints if there is one
+pub fn render_status_bar<B: Backend>(
+    f: &mut Frame<B>,
+    rect: Rect,
+    keymap: &Keymap,
+    message: Option<&str>,
+    theme: &Theme,
+) {
+    if let Some(message) = message {
+        f.render_widget(Paragraph::new(message).style(theme.status_bar), rect);
+        return;
+    }
+    let cursor: String = [Action::Up, Action::Down, Action::Left, Action::Right]
+        .into_iter()
+        .filter_map(|action| keymap.chords(action).next().map(|chord| chord.to_string()))
+        .collect();
+    let hints = [
+        ("Move Cursor", cursor),
+        ("Create Item", keymap.keys(Action::CreateRow)),
+        ("Commands", keymap.keys(Action::CommandPalette)),
+        ("Help", keymap.keys(Action::Help)),
+    ];
+    let text = hints
+        .iter()
+        .filter(|(_, keys)| !keys.is_empty())
+        .map(|(title, keys)| format!("{}: {}", title, keys))
+        .collect::<Vec<_>>()
+        .join(" | ");
     f.render_widget(
-        Paragraph::new("Move Cursor: ↑↓←→ | Create Item: c | Help: Esc")
-            .style(Style::default().add_modifier(Modifier::ITALIC | Modifier::DIM))
+        Paragraph::new(text)
+            .style(theme.status_bar)
             .alignment(Alignment::Left),
         rect,
     );
@@ -0,0 +1,52 @@
+use tui::{
+    backend::Backend,
+    layout::{Alignment, Rect},
+    text::{Span, Spans},
+    widgets::Paragraph,
+    Frame,
+};
+use unicode_width::UnicodeWidthStr;
+
+use crate::app::{board::Board, model::Model};
+
+use super::Theme;
+
+fn label(board: &Board) -> String {
+    match board.marked_count() {
+        0 => board.title(),
+        count => format!("{} ({} marked)", board.title(), count),
+    }
+}
+
+/// Renders a tab for every open board, recording where each was drawn. With
+/// just one board open its title is shown centered instead.
+pub fn render_tab_bar<B: Backend>(f: &mut Frame<B>, rect: Rect, model: &mut Model, theme: &Theme) {
+    model.tab_areas.clear();
+    if model.boards.len() == 1 {
+        f.render_widget(
+            Paragraph::new(label(&model.boards[0]))
+                .style(theme.title)
+                .alignment(Alignment::Center),
+            rect,
+        );
+        return;
+    }
+
+    let mut spans = Vec::new();
+    let mut x = rect.x;
+    for (i, board) in model.boards.iter().enumerate() {
+        let text = format!(" {} ", label(board));
+        let width = (text.width() as u16).min(rect.right().saturating_sub(x));
+        model.tab_areas.push(Rect::new(x, rect.y, width, 1));
+        x = x.saturating_add(width + 1);
+
+        let style = if i == model.tab {
+            theme.selected
+        } else {
+            theme.muted
+        };
+        spans.push(Span::styled(text, style));
+        spans.push(Span::styled("│", theme.border));
+    }
+    f.render_widget(Paragraph::new(Spans::from(spans)), rect);
+}
@@ -0,0 +1,229 @@
+use tui::style::{Color, Modifier, Style};
+
+/// The styles used by every part of the UI
+#[derive(Clone)]
+pub struct Theme {
+    /// Borders of unselected columns
+    pub border: Style,
+    /// Border of the selected column
+    pub border_selected: Style,
+    /// The selected card, or the selected entry of a list
+    pub selected: Style,
+    pub title: Style,
+    pub description: Style,
+    /// The marker in front of cards marked for a bulk operation
+    pub marked: Style,
+    pub label: Style,
+    /// Secondary text such as hints and locations
+    pub muted: Style,
+    pub popup: Style,
+    /// Border of the text field being edited
+    pub focus: Style,
+    pub button: Style,
+    pub button_focused: Style,
+    pub status_bar: Style,
+}
+
+impl Theme {
+    pub const BUILTIN: [&'static str; 4] = ["dark", "light", "high-contrast", "monochrome"];
+
+    pub fn builtin(name: &str) -> Option<Self> {
+        match name {
+            "dark" => Some(Self::dark()),
+            "light" => Some(Self::light()),
+            "high-contrast" => Some(Self::high_contrast()),
+            "monochrome" => Some(Self::monochrome()),
+            _ => None,
+        }
+    }
+
+    /// The theme used when none is configured, which respects `NO_COLOR`
+    pub fn from_env() -> Self {
+        match std::env::var_os("NO_COLOR") {
+            Some(value) if !value.is_empty() => Self::monochrome(),
+            _ => Self::dark(),
+        }
+    }
+
+    pub fn dark() -> Self {
+        Self {
+            border: Style::default().fg(Color::Blue).add_modifier(Modifier::DIM),
+            border_selected: Style::default().fg(Color::Blue),
+            selected: Style::default().fg(Color::Green),
+            title: Style::default().add_modifier(Modifier::BOLD),
+            description: Style::default().add_modifier(Modifier::ITALIC | Modifier::DIM),
+            marked: Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+            label: Style::default().fg(Color::Cyan),
+            muted: Style::default().add_modifier(Modifier::DIM),
+            popup: Style::default(),
+            focus: Style::default().fg(Color::Green),
+            button: Style::default().bg(Color::DarkGray),
+            button_focused: Style::default()
+                .bg(Color::Magenta)
+                .add_modifier(Modifier::BOLD),
+            status_bar: Style::default().add_modifier(Modifier::ITALIC | Modifier::DIM),
+        }
+    }
+
+    pub fn light() -> Self {
+        Self {
+            border: Style::default().fg(Color::Gray),
+            border_selected: Style::default().fg(Color::Blue),
+            selected: Style::default()
+                .fg(Color::Blue)
+                .add_modifier(Modifier::BOLD),
+            title: Style::default()
+                .fg(Color::Black)
+                .add_modifier(Modifier::BOLD),
+            description: Style::default()
+                .fg(Color::DarkGray)
+                .add_modifier(Modifier::ITALIC),
+            marked: Style::default()
+                .fg(Color::Magenta)
+                .add_modifier(Modifier::BOLD),
+            label: Style::default().fg(Color::Blue),
+            muted: Style::default().fg(Color::DarkGray),
+            popup: Style::default().fg(Color::Black),
+            focus: Style::default().fg(Color::Blue),
+            button: Style::default().fg(Color::Black).bg(Color::Gray),
+            button_focused: Style::default()
+                .fg(Color::White)
+                .bg(Color::Blue)
+                .add_modifier(Modifier::BOLD),
+            status_bar: Style::default()
+                .fg(Color::DarkGray)
+                .add_modifier(Modifier::ITALIC),
+        }
+    }
+
+    pub fn high_contrast() -> Self {
+        Self {
+            border: Style::default().fg(Color::White),
+            border_selected: Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+            selected: Style::default()
+                .fg(Color::Black)
+                .bg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+            title: Style::default()
+                .fg(Color::White)
+                .add_modifier(Modifier::BOLD),
+            description: Style::default().fg(Color::White),
+            marked: Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+            label: Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+            muted: Style::default().fg(Color::White),
+            popup: Style::default().fg(Color::White),
+            focus: Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+            button: Style::default().fg(Color::Black).bg(Color::White),
+            button_focused: Style::default()
+                .fg(Color::Black)
+                .bg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+            status_bar: Style::default().fg(Color::White),
+        }
+    }
+
+    /// Uses no colors at all, only text modifiers
+    pub fn monochrome() -> Self {
+        Self {
+            border: Style::default().add_modifier(Modifier::DIM),
+            border_selected: Style::default().add_modifier(Modifier::BOLD),
+            selected: Style::default().add_modifier(Modifier::REVERSED),
+            title: Style::default().add_modifier(Modifier::BOLD),
+            description: Style::default().add_modifier(Modifier::ITALIC),
+            marked: Style::default().add_modifier(Modifier::BOLD),
+            label: Style::default().add_modifier(Modifier::UNDERLINED),
+            muted: Style::default().add_modifier(Modifier::DIM),
+            popup: Style::default(),
+            focus: Style::default().add_modifier(Modifier::BOLD),
+            button: Style::default(),
+            button_focused: Style::default().add_modifier(Modifier::REVERSED | Modifier::BOLD),
+            status_bar: Style::default().add_modifier(Modifier::DIM),
+        }
+    }
+
+    /// Overrides the style of the element called `name` in the config file
+    pub fn set(&mut self, name: &str, style: Style) -> Result<(), String> {
+        let element = match name {
+            "border" => &mut self.border,
+            "border_selected" => &mut self.border_selected,
+            "selected" => &mut self.selected,
+            "title" => &mut self.title,
+            "description" => &mut self.description,
+            "marked" => &mut self.marked,
+            "label" => &mut self.label,
+            "muted" => &mut self.muted,
+            "popup" => &mut self.popup,
+            "focus" => &mut self.focus,
+            "button" => &mut self.button,
+            "button_focused" => &mut self.button_focused,
+            "status_bar" => &mut self.status_bar,
+            _ => return Err(format!("unknown theme element `{}`", name)),
+        };
+        *element = style;
+        Ok(())
+    }
+}
+
+/// Parses a color name, a `#rrggbb` hex code or a 256-color palette index
+pub fn parse_color(s: &str) -> Result<Color, String> {
+    if let Some(hex) = s.strip_prefix('#') {
+        let channel = |i: usize| {
+            hex.get(i..i + 2)
+                .and_then(|c| u8::from_str_radix(c, 16).ok())
+        };
+        return match (hex.len(), channel(0), channel(2), channel(4)) {
+            (6, Some(r), Some(g), Some(b)) => Ok(Color::Rgb(r, g, b)),
+            _ => Err(format!("invalid hex color `{}`", s)),
+        };
+    }
+    if let Ok(index) = s.parse::<u8>() {
+        return Ok(Color::Indexed(index));
+    }
+    let color = match s.to_lowercase().replace(['_', '-', ' '], "").as_str() {
+        "reset" | "default" => Color::Reset,
+        "black" => Color::Black,
+        "red" => Color::Red,
+        "green" => Color::Green,
+        "yellow" => Color::Yellow,
+        "blue" => Color::Blue,
+        "magenta" => Color::Magenta,
+        "cyan" => Color::Cyan,
+        "gray" | "grey" => Color::Gray,
+        "darkgray" | "darkgrey" => Color::DarkGray,
+        "lightred" => Color::LightRed,
+        "lightgreen" => Color::LightGreen,
+        "lightyellow" => Color::LightYellow,
+        "lightblue" => Color::LightBlue,
+        "lightmagenta" => Color::LightMagenta,
+        "lightcyan" => Color::LightCyan,
+        "white" => Color::White,
+        _ => return Err(format!("unknown color `{}`", s)),
+    };
+    Ok(color)
+}
+
+pub fn parse_modifier(s: &str) -> Result<Modifier, String> {
+    let modifier = match s.to_lowercase().as_str() {
+        "bold" => Modifier::BOLD,
+        "dim" => Modifier::DIM,
+        "italic" => Modifier::ITALIC,
+        "underlined" | "underline" => Modifier::UNDERLINED,
+        "slow_blink" => Modifier::SLOW_BLINK,
+        "rapid_blink" => Modifier::RAPID_BLINK,
+        "reversed" => Modifier::REVERSED,
+        "hidden" => Modifier::HIDDEN,
+        "crossed_out" => Modifier::CROSSED_OUT,
+        _ => return Err(format!("unknown modifier `{}`", s)),
+    };
+    Ok(modifier)
+}
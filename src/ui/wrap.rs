@@ -0,0 +1,167 @@
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// Wraps text into lines no wider than `width` terminal columns, breaking
+/// between words where possible. Words wider than a line are split, with a
+/// hyphen when the split falls between two letters.
+pub fn wrap(text: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let mut lines = Vec::new();
+    for paragraph in text.lines() {
+        let words = paragraph.trim_start();
+        let indent = &paragraph[..paragraph.len() - words.len()];
+        let mut line = if indent.width() < width {
+            indent.to_string()
+        } else {
+            String::new()
+        };
+        let mut line_width = line.width();
+        let mut empty = true;
+
+        for word in words.split_whitespace() {
+            let word_width = word.width();
+            let needed = if empty { word_width } else { word_width + 1 };
+            if line_width + needed <= width {
+                if !empty {
+                    line.push(' ');
+                }
+                line.push_str(word);
+                line_width += needed;
+                empty = false;
+                continue;
+            }
+            if !empty {
+                lines.push(std::mem::take(&mut line));
+                line_width = 0;
+            }
+            if word_width <= width {
+                // A first word that only fits without the indent goes
+                // without it
+                line.clear();
+                line.push_str(word);
+                line_width = word_width;
+            } else {
+                split_word(word, width, &mut line, &mut line_width, &mut lines);
+            }
+            empty = false;
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+/// Cuts `lines` down to `max_lines`, ending the last line kept with an
+/// ellipsis if anything was cut
+pub fn truncate(mut lines: Vec<String>, max_lines: usize, width: usize) -> Vec<String> {
+    if lines.len() <= max_lines {
+        return lines;
+    }
+    lines.truncate(max_lines);
+    if let Some(last) = lines.last_mut() {
+        let mut graphemes: Vec<&str> = last.graphemes(true).collect();
+        while !graphemes.is_empty() && graphemes.concat().width() + 1 > width {
+            graphemes.pop();
+        }
+        *last = format!("{}…", graphemes.concat().trim_end());
+    }
+    lines
+}
+
+/// Splits a word that is too long for a line of its own across as many lines
+/// as it needs. The last part is left in `line` so that following words can
+/// join it.
+fn split_word(
+    word: &str,
+    width: usize,
+    line: &mut String,
+    line_width: &mut usize,
+    lines: &mut Vec<String>,
+) {
+    let graphemes: Vec<&str> = word.graphemes(true).collect();
+    let mut start = 0;
+    while start < graphemes.len() {
+        let available = width.saturating_sub(*line_width);
+        let mut end = start;
+        let mut used = 0;
+        while end < graphemes.len() && used + graphemes[end].width() <= available {
+            used += graphemes[end].width();
+            end += 1;
+        }
+        if end == graphemes.len() {
+            line.push_str(&graphemes[start..end].concat());
+            *line_width += used;
+            return;
+        }
+        // Make room for the hyphen
+        if end > start + 1 && hyphenate(&graphemes, end) && used + 1 > available {
+            end -= 1;
+        }
+        if end == start {
+            if *line_width > 0 {
+                lines.push(std::mem::take(line));
+                *line_width = 0;
+                continue;
+            }
+            // Even a single character doesn't fit, so let it overflow
+            end = start + 1;
+        }
+        line.push_str(&graphemes[start..end].concat());
+        if hyphenate(&graphemes, end) && end > start + 1 {
+            line.push('-');
+        }
+        lines.push(std::mem::take(line));
+        *line_width = 0;
+        start = end;
+    }
+}
+
+/// Whether splitting before `graphemes[index]` needs a hyphen. Wide
+/// characters such as CJK can be split anywhere without one.
+fn hyphenate(graphemes: &[&str], index: usize) -> bool {
+    let is_letter = |g: &str| g.width() == 1 && g.chars().all(char::is_alphanumeric);
+    index > 0
+        && index < graphemes.len()
+        && is_letter(graphemes[index - 1])
+        && is_letter(graphemes[index])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn widths(lines: &[String]) -> Vec<usize> {
+        lines.iter().map(|line| line.width()).collect()
+    }
+
+    #[test]
+    fn keeps_the_indent() {
+        assert_eq!(wrap("  one two three", 9), ["  one two", "three"]);
+    }
+
+    #[test]
+    fn drops_the_indent_for_a_word_that_only_fits_without_it() {
+        assert_eq!(wrap("    abcdefgh", 10), ["abcdefgh"]);
+    }
+
+    #[test]
+    fn splits_words_wider_than_a_line() {
+        assert_eq!(wrap("abcdefghij klm", 4), ["abc-", "def-", "ghij", "klm"]);
+        let lines = wrap("  abcdefghijkl", 6);
+        assert_eq!(lines, ["  abc-", "defgh-", "ijkl"]);
+        assert!(widths(&lines).iter().all(|&width| width <= 6));
+    }
+
+    #[test]
+    fn splits_wide_characters_by_display_width() {
+        let lines = wrap("日本語のテキスト", 5);
+        assert_eq!(lines, ["日本", "語の", "テキ", "スト"]);
+        assert_eq!(widths(&wrap("日本 語", 4)), [4, 2]);
+    }
+
+    #[test]
+    fn truncates_with_an_ellipsis() {
+        let lines = vec!["one two".to_string(), "three".to_string()];
+        assert_eq!(truncate(lines.clone(), 2, 7), lines);
+        assert_eq!(truncate(lines, 1, 7), ["one tw…"]);
+    }
+}